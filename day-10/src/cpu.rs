@@ -1,5 +1,7 @@
-pub use self::crt::CRT;
+pub use self::crt::{CRT, Frame, ImageFormat};
+pub use self::trace::TraceEntry;
 mod crt;
+mod trace;
 
 // CPU instruction set
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -17,12 +19,14 @@ pub struct CPU {
     carry: i32,        
     breakpoints: Vec<i32>,
     trace: Vec<i32>,
+    full_trace: Option<Vec<TraceEntry>>,
     display: CRT
 }
 
 impl CPU {
     
     // Create a new CPU object. Pass in a display to use with the CPU
+    #[allow(clippy::redundant_field_names)]
     pub fn new(display: CRT) -> Self {
         Self {
             x: 1,
@@ -31,12 +35,14 @@ impl CPU {
             carry: 0,            
             breakpoints: Vec::new(),
             trace: Vec::new(),
+            full_trace: None,
             display: display
         }
     }
 
     // Pass in a list of breakpoints that are trigger at specific cycle counts
     // When a breakpoints is triggered, signal strength is inserted into the trace log
+    #[allow(clippy::unused_unit)]
     pub fn set_breakpoints(&mut self, points: Vec<i32>) -> () {        
         self.breakpoints = points;
         self.breakpoints.sort();
@@ -47,13 +53,48 @@ impl CPU {
         self.trace.clone()
     }
 
+    // Enable or disable recording of every cycle into the full trace
+    // Disabling recording discards any entries captured so far
+    pub fn enable_full_trace(&mut self, enable: bool) {
+        self.full_trace = if enable { Some(Vec::new()) } else { None };
+    }
+
+    // Returns the full per-cycle trace (empty unless enabled before running)
+    pub fn get_full_trace(&self) -> &[TraceEntry] {
+        self.full_trace.as_deref().unwrap_or(&[])
+    }
+
+    // Returns the signal strength during an arbitrary cycle from the full trace
+    pub fn signal_strength_at(&self, cycle: i32) -> Option<i32> {
+        self.get_full_trace()
+         .iter()
+         .find(|e| e.cycle == cycle)
+         .map(|e| e.signal_strength())
+    }
+
+    // Export the full trace as CSV
+    pub fn export_trace_csv(&self) -> String {
+        trace::to_csv(self.get_full_trace())
+    }
+
+    // Export the full trace as JSON
+    pub fn export_trace_json(&self) -> String {
+        trace::to_json(self.get_full_trace())
+    }
+
+    // Returns the display attached to the CPU
+    pub fn get_display(&self) -> &CRT {
+        &self.display
+    }
+
     // Run a program (list of instructions) and output the result
     // using the display
+    #[allow(clippy::ptr_arg, clippy::unused_unit, clippy::while_let_on_iterator, clippy::single_match, clippy::collapsible_match)]
     pub fn run_program(&mut self, p: &Vec<Op>) -> () {
-        let mut iter = p.iter();
+        let mut iter = p.iter().enumerate();
         let mut break_iter = self.breakpoints.iter();
         let mut bkpt = break_iter.next();
-        while let Some(instruction) = iter.next() {
+        while let Some((index, instruction)) = iter.next() {
             while self.carry > 0 {
                 self.carry -= 1;
                 self.tick += 1;
@@ -70,12 +111,26 @@ impl CPU {
                 }                
 
                 // Update display
-                self.display.draw(self.x);
+                let (column, row) = self.display.cursor();
+                let lit = self.display.draw(self.x);
+                let x_during = self.x;
 
                 // Apply scratch register at end of cycle
                 if self.carry == 0 {
                     self.x += self.y;
                 }
+
+                if let Some(full_trace) = &mut self.full_trace {
+                    full_trace.push(TraceEntry {
+                        cycle: self.tick,
+                        instruction: index - 1,
+                        x_during,
+                        x_after: self.x,
+                        column,
+                        row,
+                        lit
+                    });
+                }
             }
 
             // Handle next instruction
@@ -93,4 +148,30 @@ impl CPU {
         // Print program output
         println!("{:?}", self.display);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn run_sample(full_trace: bool) -> CPU {
+    // An instruction only runs once the next one is read, so the trailing
+    // NoOp lets AddX(-5) finish
+    let program = vec![Op::NoOp, Op::AddX(3), Op::AddX(-5), Op::NoOp];
+    let mut cpu = CPU::new(CRT::new(5, 1));
+    cpu.enable_full_trace(full_trace);
+    cpu.set_breakpoints(vec![2, 5]);
+    cpu.run_program(&program);
+    cpu
+}
+
+#[test]
+fn test_full_trace() {
+    let cpu = run_sample(true);
+    let xs: Vec<(i32, usize, i32, i32)> = cpu.get_full_trace()
+     .iter()
+     .map(|e| (e.cycle, e.instruction, e.x_during, e.x_after))
+     .collect();
+    assert_eq!(xs, vec![(1, 0, 1, 1), (2, 1, 1, 1), (3, 1, 1, 4), (4, 2, 4, 4), (5, 2, 4, -1)]);
+    assert_eq!(cpu.get_trace_log(), vec![2, 20]);
+    assert_eq!(cpu.signal_strength_at(5), Some(20));
+    assert_eq!(cpu.signal_strength_at(6), None);
+    assert_eq!(run_sample(false).get_full_trace(), &[]);
+}
@@ -1,48 +1,72 @@
+pub use self::export::ImageFormat;
+mod export;
+
 use std::fmt;
 
+// A single snapshot of the display pixels (row major)
+pub type Frame = Vec<Vec<bool>>;
+
 // CRT display type
 #[derive(Clone, PartialEq)]
 pub struct CRT {
     width: usize,
     height: usize,
     index: usize,
-    pixels: Vec<Vec<bool>>,
+    pixels: Frame,
+    frames: Option<Vec<Frame>>,
 }
 
 impl CRT {
     
     // Create a new display with a fixed width and height
+    #[allow(clippy::redundant_field_names)]
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             width: width,
             height: height,
             index: 0,
-            pixels: vec![vec![false; width]; height]
+            pixels: vec![vec![false; width]; height],
+            frames: None
         }
     }
 
+    // Enable or disable recording of a frame after every draw call
+    // Disabling recording discards any frames captured so far
+    pub fn record_frames(&mut self, enable: bool) {
+        self.frames = if enable { Some(Vec::new()) } else { None };
+    }
+
+    // Returns the frames recorded so far (one per draw call)
+    pub fn get_frames(&self) -> &[Frame] {
+        self.frames.as_deref().unwrap_or(&[])
+    }
+
+    // Returns the (column, row) of the pixel the next draw call will update
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.index % self.width, self.index / self.width)
+    }
+
     // Iterative draw method. Updates internal draw index
     // pixels turned on if sprite location matches current index (+ tolerance)
-    pub fn draw(&mut self, sprite_location: i32) -> () {
-        let px = self.index % self.width;
-        let py = self.index / self.width;
+    // Returns whether the pixel was lit
+    pub fn draw(&mut self, sprite_location: i32) -> bool {
+        let (px, py) = self.cursor();
         let delta = sprite_location - px as i32;
-        if delta.abs() <= 1 {            
+        let lit = delta.abs() <= 1;
+        if lit {
             self.pixels[py][px] = true;
         }
         self.index += 1;
+        if let Some(frames) = &mut self.frames {
+            frames.push(self.pixels.clone());
+        }
+        lit
     }
 }
 
 impl fmt::Debug for CRT {
     // Implement custom debug format trait to print output
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for i in 0..self.height {
-            for j in 0..self.width {
-                write!(f, "{}", if self.pixels[i][j] {'#'} else {','})?;
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
+        write!(f, "{}", self.to_text('#', ','))
     }
-}
\ No newline at end of file
+}
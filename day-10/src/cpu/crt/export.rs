@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    io,
+    path::Path,
+};
+
+use super::{CRT, Frame};
+
+// Grey levels used for lit and unlit pixels in the greyscale formats.
+// Lit pixels are drawn dark so images match the text rendering
+const LIT: u8 = 0;
+const UNLIT: u8 = 255;
+
+// Supported output formats for the display
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageFormat {
+    // Plain text using the given characters for lit and unlit pixels
+    Text { on: char, off: char },
+    // Plain (ASCII) portable bitmap
+    Pbm,
+    // Plain (ASCII) portable greymap
+    Pgm,
+    // 8-bit greyscale PNG
+    Png,
+}
+
+impl ImageFormat {
+    // File extension conventionally used for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Text { .. } => "txt",
+            ImageFormat::Pbm => "pbm",
+            ImageFormat::Pgm => "pgm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+impl CRT {
+
+    // Render the current display as text using custom characters
+    pub fn to_text(&self, on: char, off: char) -> String {
+        render_text(&self.pixels, on, off)
+    }
+
+    // Encode the current display in the requested format
+    pub fn encode(&self, format: ImageFormat) -> io::Result<Vec<u8>> {
+        encode_frame(&self.pixels, format)
+    }
+
+    // Write the current display to a file in the requested format
+    pub fn save(&self, path: impl AsRef<Path>, format: ImageFormat) -> io::Result<()> {
+        fs::write(path, self.encode(format)?)
+    }
+
+    // Write every recorded frame into a directory as individually numbered files
+    // (frame_0000.png, frame_0001.png, ...). Returns the number of frames written
+    pub fn dump_frames(&self, dir: impl AsRef<Path>, format: ImageFormat) -> io::Result<usize> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let frames = self.get_frames();
+        for (i, frame) in frames.iter().enumerate() {
+            let name = format!("frame_{:04}.{}", i, format.extension());
+            fs::write(dir.join(name), encode_frame(frame, format)?)?;
+        }
+        Ok(frames.len())
+    }
+
+    // Write every recorded frame into a single animated PNG, showing each
+    // frame for `delay_ms` milliseconds and looping forever
+    pub fn save_animation(&self, path: impl AsRef<Path>, delay_ms: u16) -> io::Result<()> {
+        let frames = self.get_frames();
+        if frames.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no frames have been recorded"));
+        }
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        let mut encoder = png_encoder(file, self.width, self.height);
+        encoder.set_animated(frames.len() as u32, 0)?;
+        encoder.set_frame_delay(delay_ms, 1000)?;
+        let mut writer = encoder.write_header()?;
+        for frame in frames {
+            writer.write_image_data(&greyscale(frame))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+// Render a frame as lines of text
+fn render_text(frame: &Frame, on: char, off: char) -> String {
+    frame.iter()
+     .map(|row| row.iter().map(|&p| if p { on } else { off }).collect::<String>() + "\n")
+     .collect()
+}
+
+// Encode a single frame in the requested format
+fn encode_frame(frame: &Frame, format: ImageFormat) -> io::Result<Vec<u8>> {
+    let (width, height) = (frame.first().map_or(0, |r| r.len()), frame.len());
+    let data = match format {
+        ImageFormat::Text { on, off } => render_text(frame, on, off).into_bytes(),
+        ImageFormat::Pbm => {
+            // PBM uses 1 for black, which is how lit pixels are drawn
+            let body = render_text(frame, '1', '0');
+            format!("P1\n{} {}\n{}", width, height, body).into_bytes()
+        },
+        ImageFormat::Pgm => {
+            let body: String = frame.iter()
+             .map(|row| row.iter().map(|&p| level(p).to_string()).collect::<Vec<_>>().join(" ") + "\n")
+             .collect();
+            format!("P2\n{} {}\n255\n{}", width, height, body).into_bytes()
+        },
+        ImageFormat::Png => {
+            let mut data = Vec::new();
+            let mut writer = png_encoder(&mut data, width, height).write_header()?;
+            writer.write_image_data(&greyscale(frame))?;
+            writer.finish()?;
+            data
+        }
+    };
+    Ok(data)
+}
+
+// Create a PNG encoder configured for 8-bit greyscale output
+fn png_encoder<W: io::Write>(w: W, width: usize, height: usize) -> png::Encoder<'static, W> {
+    let mut encoder = png::Encoder::new(w, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+}
+
+// Flatten a frame into one grey level byte per pixel
+fn greyscale(frame: &Frame) -> Vec<u8> {
+    frame.iter().flatten().map(|&p| level(p)).collect()
+}
+
+fn level(p: bool) -> u8 {
+    if p { LIT } else { UNLIT }
+}
+
+#[cfg(test)]
+fn sample_crt() -> CRT {
+    let mut crt = CRT::new(3, 2);
+    crt.record_frames(true);
+    for x in [0, 5, 2, 1, 1, 9] {
+        crt.draw(x);
+    }
+    crt
+}
+
+#[test]
+fn test_text_export() {
+    let crt = sample_crt();
+    assert_eq!(crt.to_text('#', '.'), "#.#\n##.\n");
+    assert_eq!(crt.encode(ImageFormat::Text { on: 'X', off: ' ' }).unwrap(), b"X X\nXX \n");
+    assert_eq!(format!("{:?}", crt), "#,#\n##,\n");
+}
+
+#[test]
+fn test_netpbm_export() {
+    let crt = sample_crt();
+    assert_eq!(crt.encode(ImageFormat::Pbm).unwrap(), b"P1\n3 2\n101\n110\n");
+    assert_eq!(crt.encode(ImageFormat::Pgm).unwrap(), b"P2\n3 2\n255\n0 255 0\n0 0 255\n");
+}
+
+#[test]
+fn test_png_export() {
+    let crt = sample_crt();
+    let data = crt.encode(ImageFormat::Png).unwrap();
+    let mut reader = png::Decoder::new(io::Cursor::new(data)).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert_eq!((info.width, info.height), (3, 2));
+    assert_eq!(&buf[..info.buffer_size()], &[0, 255, 0, 0, 0, 255]);
+}
+
+#[test]
+fn test_frame_dump() -> io::Result<()> {
+    let crt = sample_crt();
+    assert_eq!(crt.get_frames().len(), 6);
+    assert_eq!(render_text(&crt.get_frames()[0], '#', '.'), "#..\n...\n");
+
+    let dir = std::env::temp_dir().join(format!("day-10-frames-{}", std::process::id()));
+    assert_eq!(crt.dump_frames(&dir, ImageFormat::Pbm)?, 6);
+    assert_eq!(fs::read(dir.join("frame_0005.pbm"))?, crt.encode(ImageFormat::Pbm)?);
+    crt.save_animation(dir.join("animation.png"), 100)?;
+    let reader = png::Decoder::new(io::BufReader::new(fs::File::open(dir.join("animation.png"))?)).read_info()?;
+    assert_eq!(reader.info().animation_control.map(|a| a.num_frames), Some(6));
+    fs::remove_dir_all(dir)
+}
@@ -0,0 +1,62 @@
+// A single CPU cycle captured by the full trace
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TraceEntry {
+    // Cycle number (starting at 1)
+    pub cycle: i32,
+    // Index into the program of the instruction executing during the cycle
+    pub instruction: usize,
+    // Value of the x register during the cycle
+    pub x_during: i32,
+    // Value of the x register once the cycle has completed
+    pub x_after: i32,
+    // Display pixel drawn during the cycle
+    pub column: usize,
+    pub row: usize,
+    // Whether the pixel was lit
+    pub lit: bool,
+}
+
+impl TraceEntry {
+    // Signal strength during the cycle (cycle number times the x register)
+    pub fn signal_strength(&self) -> i32 {
+        self.cycle * self.x_during
+    }
+}
+
+const CSV_HEADER: &str = "cycle,instruction,x_during,x_after,column,row,lit";
+
+// Serialize a trace as CSV with a header row
+pub fn to_csv(trace: &[TraceEntry]) -> String {
+    let mut s = String::from(CSV_HEADER);
+    s.push('\n');
+    for e in trace {
+        s += &format!("{},{},{},{},{},{},{}\n", e.cycle, e.instruction, e.x_during, e.x_after, e.column, e.row, e.lit);
+    }
+    s
+}
+
+// Serialize a trace as a JSON array of objects
+pub fn to_json(trace: &[TraceEntry]) -> String {
+    let entries: Vec<String> = trace.iter()
+     .map(|e| format!(
+        "{{\"cycle\":{},\"instruction\":{},\"x_during\":{},\"x_after\":{},\"column\":{},\"row\":{},\"lit\":{}}}",
+        e.cycle, e.instruction, e.x_during, e.x_after, e.column, e.row, e.lit))
+     .collect();
+    format!("[{}]", entries.join(","))
+}
+
+#[cfg(test)]
+fn sample_trace() -> Vec<TraceEntry> {
+    vec![
+        TraceEntry { cycle: 1, instruction: 0, x_during: 1, x_after: 1, column: 0, row: 0, lit: true },
+        TraceEntry { cycle: 2, instruction: 1, x_during: 1, x_after: -4, column: 1, row: 0, lit: true },
+    ]
+}
+
+#[test]
+fn test_trace_export() {
+    let trace = sample_trace();
+    assert_eq!(to_csv(&trace), "cycle,instruction,x_during,x_after,column,row,lit\n1,0,1,1,0,0,true\n2,1,1,-4,1,0,true\n");
+    assert_eq!(to_json(&trace[1..]), "[{\"cycle\":2,\"instruction\":1,\"x_during\":1,\"x_after\":-4,\"column\":1,\"row\":0,\"lit\":true}]");
+    assert_eq!(to_json(&[]), "[]");
+}
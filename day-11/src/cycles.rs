@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::monkey::Monkey;
+use crate::simulation::WorryError;
+use crate::worry::{EvalError, Worry};
+
+// Run a single item through one round. Returns the monkey holding it at the start
+// of the next round and its worry level. Inspections are added to `counts`
+pub(crate) fn item_round<W: Worry>(monkeys: &[Monkey<W>], round: usize, (mut monkey, mut worry): (usize, W), counts: &mut [u64]) -> Result<(usize, W), WorryError> {
+    loop {
+        let (to, new_worry) = monkeys.get(monkey)
+         .ok_or(EvalError::UnknownMonkey(monkey))
+         .and_then(|m| m.inspect(&worry))
+         .map_err(|error| WorryError { round, monkey, error })?;
+        counts[monkey] += 1;
+        worry = new_worry;
+        // Monkeys later in the order inspect the item again this round
+        let from = monkey;
+        monkey = to;
+        if to <= from {
+            return Ok((monkey, worry));
+        }
+    }
+}
+
+// Count the inspections each monkey makes of a single item over a number of rounds.
+// Items evolve independently of one another, so the item's state at the start of
+// each round (holder, worry) is tracked until it repeats, after which the counts
+// of the cycle are extrapolated. With a common modulus applied the state space is
+// finite, so the run time is bounded by the cycle length rather than `rounds`
+pub fn item_inspections<W>(monkeys: &[Monkey<W>], monkey: usize, worry: W, rounds: u64) -> Result<Vec<u64>, WorryError>
+where
+    W: Worry + Hash + Eq
+{
+    let mut seen: HashMap<(usize, W), usize> = HashMap::new();
+    // cumulative[r] holds the counts after r rounds
+    let mut cumulative: Vec<Vec<u64>> = vec![vec![0; monkeys.len()]];
+    let mut state = (monkey, worry);
+
+    loop {
+        let r = cumulative.len() - 1;
+        if r as u64 == rounds {
+            return Ok(cumulative.pop().unwrap());
+        }
+        if let Some(&first) = seen.get(&state) {
+            let length = (r - first) as u64;
+            let remaining = rounds - r as u64;
+            let (cycles, rest) = (remaining / length, (remaining % length) as usize);
+            let counts = (0..monkeys.len())
+             .map(|i| {
+                let per_cycle = cumulative[r][i] - cumulative[first][i];
+                let partial = cumulative[first + rest][i] - cumulative[first][i];
+                cumulative[r][i] + cycles * per_cycle + partial
+             })
+             .collect();
+            return Ok(counts);
+        }
+        seen.insert(state.clone(), r);
+
+        let mut counts = cumulative[r].clone();
+        state = item_round(monkeys, r + 1, state, &mut counts)?;
+        cumulative.push(counts);
+    }
+}
+
+// Activity count of each monkey after a number of rounds, computed per item with
+// cycle detection. Monkeys are not modified
+pub fn extrapolate_activity<W>(monkeys: &[Monkey<W>], rounds: u64) -> Result<Vec<u64>, WorryError>
+where
+    W: Worry + Hash + Eq
+{
+    let mut activity: Vec<u64> = monkeys.iter().map(|m| m.activity as u64).collect();
+    for (i, monkey) in monkeys.iter().enumerate() {
+        for item in &monkey.items {
+            let counts = item_inspections(monkeys, i, item.clone(), rounds)?;
+            for (total, count) in activity.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+    }
+    Ok(activity)
+}
+
+// Monkey business score for an arbitrary number of rounds
+pub fn get_monkey_business_extrapolated<W>(monkeys: &[Monkey<W>], rounds: u64) -> Result<u128, WorryError>
+where
+    W: Worry + Hash + Eq
+{
+    let mut activity = extrapolate_activity(monkeys, rounds)?;
+    activity.sort_by(|a, b| b.cmp(a));
+    Ok(activity.iter().take(2).map(|&x| x as u128).product())
+}
+
+#[cfg(test)]
+use crate::simulation::sample_monkeys;
+
+#[test]
+fn test_extrapolation_matches_simulation() {
+    let mut monkeys = sample_monkeys();
+    let activity: Vec<u64> = crate::simulation::simulate(&mut monkeys.clone(), 20).unwrap()
+     .into_iter().map(|x| x as u64).collect();
+    assert_eq!(extrapolate_activity(&monkeys, 20).unwrap(), activity);
+
+    crate::simulation::apply_common_modulus(&mut monkeys);
+    for rounds in [1, 1000, 10000] {
+        let activity: Vec<u64> = crate::simulation::simulate(&mut monkeys.clone(), rounds).unwrap()
+         .into_iter().map(|x| x as u64).collect();
+        assert_eq!(extrapolate_activity(&monkeys, rounds as u64).unwrap(), activity);
+    }
+    assert_eq!(get_monkey_business_extrapolated(&monkeys, 10000), Ok(2713310158));
+}
+
+#[test]
+fn test_extrapolation_of_many_rounds() {
+    let mut monkeys = sample_monkeys();
+    crate::simulation::apply_common_modulus(&mut monkeys);
+    let activity = extrapolate_activity(&monkeys, 1_000_000_000_000).unwrap();
+    // Every item is inspected at least once per round
+    assert!(activity.iter().sum::<u64>() >= 10 * 1_000_000_000_000);
+}
+
+#[test]
+fn test_extrapolation_unknown_target() {
+    let mut monkeys = sample_monkeys();
+    monkeys[1].b = 4;
+    let err = extrapolate_activity(&monkeys, 20).unwrap_err();
+    assert_eq!(err.error, EvalError::UnknownMonkey(4));
+    assert_eq!(item_inspections(&monkeys, 7, 79, 20).unwrap_err().error, EvalError::UnknownMonkey(7));
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::worry::{EvalError, Worry};
+
+// Binary operators supported in a monkey operation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinOp {
+    // Binding strength of the operator, higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div => 2,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+        }
+    }
+}
+
+// Expression tree for the right hand side of an `Operation: new = ...` line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Old,
+    Const(u64),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+}
+
+// Errors produced while parsing an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    // Character that cannot start any token
+    UnexpectedCharacter(char),
+    // Numeric literal that does not fit the worry level type
+    InvalidNumber(String),
+    // Token that is not valid at this point in the expression
+    UnexpectedToken(String),
+    // Input ended while more of the expression was expected
+    UnexpectedEnd,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            ParseError::InvalidNumber(s)       => write!(f, "invalid number '{}'", s),
+            ParseError::UnexpectedToken(s)     => write!(f, "unexpected token '{}'", s),
+            ParseError::UnexpectedEnd          => write!(f, "unexpected end of expression"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Old,
+    Number(u64),
+    Op(BinOp),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Old       => write!(f, "old"),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Op(op)    => write!(f, "{}", op.symbol()),
+            Token::Open      => write!(f, "("),
+            Token::Close     => write!(f, ")"),
+        }
+    }
+}
+
+// Split an expression into tokens
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => { chars.next(); },
+            '+' => { chars.next(); tokens.push(Token::Op(BinOp::Add)); },
+            '-' => { chars.next(); tokens.push(Token::Op(BinOp::Sub)); },
+            '*' => { chars.next(); tokens.push(Token::Op(BinOp::Mul)); },
+            '/' => { chars.next(); tokens.push(Token::Op(BinOp::Div)); },
+            '(' => { chars.next(); tokens.push(Token::Open); },
+            ')' => { chars.next(); tokens.push(Token::Close); },
+            '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "old" => Token::Old,
+                    w if w.starts_with(|c: char| c.is_ascii_digit()) =>
+                        Token::Number(w.parse().map_err(|_| ParseError::InvalidNumber(word.clone()))?),
+                    _ => return Err(ParseError::UnexpectedToken(word)),
+                });
+            },
+            _ => return Err(ParseError::UnexpectedCharacter(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+// Precedence climbing parser over a token stream
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_op(&self) -> Option<BinOp> {
+        match self.tokens.get(self.position) {
+            Some(Token::Op(op)) => Some(*op),
+            _ => None,
+        }
+    }
+
+    // Parse a binary expression whose operators bind at least as tightly as `min_precedence`
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.operand()?;
+        while let Some(op) = self.peek_op().filter(|op| op.precedence() >= min_precedence) {
+            self.position += 1;
+            let rhs = self.expression(op.precedence() + 1)?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // Parse a single value or parenthesised expression
+    fn operand(&mut self) -> Result<Expr, ParseError> {
+        match self.next() {
+            Some(Token::Old)       => Ok(Expr::Old),
+            Some(Token::Number(n)) => Ok(Expr::Const(n)),
+            Some(Token::Open)      => {
+                let inner = self.expression(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    Some(t)            => Err(ParseError::UnexpectedToken(t.to_string())),
+                    None               => Err(ParseError::UnexpectedEnd),
+                }
+            },
+            Some(t) => Err(ParseError::UnexpectedToken(t.to_string())),
+            None    => Err(ParseError::UnexpectedEnd),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    // Parse arithmetic over `old` and integer constants. `*` and `/` bind tighter
+    // than `+` and `-`, operators are left associative and parentheses group
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(s)?, position: 0 };
+        let expr = parser.expression(0)?;
+        match parser.next() {
+            Some(t) => Err(ParseError::UnexpectedToken(t.to_string())),
+            None    => Ok(expr),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    // Fully parenthesised output so the structure is unambiguous
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Old              => write!(f, "old"),
+            Expr::Const(n)         => write!(f, "{}", n),
+            Expr::Binary(l, op, r) => write!(f, "({} {} {})", l, op.symbol(), r),
+        }
+    }
+}
+
+impl Expr {
+    // Evaluate the expression for a given old worry level
+    pub fn eval<W: Worry>(&self, old: &W) -> Result<W, EvalError> {
+        match self {
+            Expr::Old      => Ok(old.clone()),
+            Expr::Const(n) => Ok(W::from_u64(*n)),
+            Expr::Binary(l, op, r) => W::apply(*op, l.eval(old)?, r.eval(old)?),
+        }
+    }
+
+    // Check if reducing `old` modulo m leaves the result unchanged modulo m,
+    // which holds when the expression only adds and multiplies
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Const(_) => true,
+            Expr::Binary(l, op, r) => matches!(op, BinOp::Add | BinOp::Mul) && l.is_modular() && r.is_modular(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_precedence() {
+    let e: Expr = "old + 2 * old - 6 / 3".parse().unwrap();
+    assert_eq!(e.to_string(), "((old + (2 * old)) - (6 / 3))");
+    assert_eq!(e.eval(&5u64), Ok(13));
+    assert!(!e.is_modular());
+    let e: Expr = "(old + 2) * (old+1)".parse().unwrap();
+    assert_eq!(e.eval(&3u64), Ok(20));
+    assert!(e.is_modular());
+    assert_eq!("old - 8".parse::<Expr>().unwrap().eval(&7u64), Err(EvalError::Negative));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!("old ^ 2".parse::<Expr>(), Err(ParseError::UnexpectedCharacter('^')));
+    assert_eq!("old +".parse::<Expr>(), Err(ParseError::UnexpectedEnd));
+    assert_eq!("(old + 1".parse::<Expr>(), Err(ParseError::UnexpectedEnd));
+    assert_eq!("old 3".parse::<Expr>(), Err(ParseError::UnexpectedToken("3".to_string())));
+    assert_eq!("new + 1".parse::<Expr>(), Err(ParseError::UnexpectedToken("new".to_string())));
+    assert_eq!("3x".parse::<Expr>(), Err(ParseError::InvalidNumber("3x".to_string())));
+    assert_eq!("99999999999999999999".parse::<Expr>(), Err(ParseError::InvalidNumber("99999999999999999999".to_string())));
+}
@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+
+use crate::monkey::Monkey;
+use crate::simulation::WorryError;
+use crate::worry::{EvalError, Worry};
+
+// An item tagged with the index it had in the starting layout. Starting items
+// are numbered in order of monkey, then position in that monkey's list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item<W> {
+    pub id: usize,
+    pub worry: W,
+}
+
+// State at the end of a round
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundSnapshot<W> {
+    pub round: usize,
+    // Items held by each monkey, in queue order
+    pub items: Vec<Vec<Item<W>>>,
+    // Number of inspections made by each monkey during this round
+    pub inspections: Vec<usize>,
+}
+
+// A single throw of an item between monkeys
+#[derive(Debug, Clone, PartialEq)]
+pub struct Throw<W> {
+    pub round: usize,
+    pub from: usize,
+    pub to: usize,
+    // Worry level after inspection
+    pub worry: W,
+}
+
+// Full record of a simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct History<W> {
+    // Items each monkey started with
+    pub start: Vec<Vec<Item<W>>>,
+    pub rounds: Vec<RoundSnapshot<W>>,
+    // Every throw of each item, indexed by item id
+    pub throws: Vec<Vec<Throw<W>>>,
+}
+
+impl<W: Worry> History<W> {
+    // Number of items being tracked
+    pub fn item_count(&self) -> usize {
+        self.throws.len()
+    }
+
+    // Monkey that started with an item
+    pub fn starting_monkey(&self, item: usize) -> Option<usize> {
+        self.start.iter().position(|items| items.iter().any(|x| x.id == item))
+    }
+
+    // Every throw of a starting item, in order
+    pub fn trajectory(&self, item: usize) -> &[Throw<W>] {
+        self.throws.get(item).map_or(&[], |x| x.as_slice())
+    }
+
+    // Sequence of monkeys that held an item, starting with its first owner
+    pub fn path(&self, item: usize) -> Vec<usize> {
+        self.starting_monkey(item)
+         .into_iter()
+         .chain(self.trajectory(item).iter().map(|t| t.to))
+         .collect()
+    }
+
+    // Total inspections made by each monkey over every recorded round
+    pub fn total_inspections(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.start.len()];
+        for round in &self.rounds {
+            for (total, count) in totals.iter_mut().zip(&round.inspections) {
+                *total += count;
+            }
+        }
+        totals
+    }
+
+    // Per round statistics as CSV, one row per monkey per round. Items are
+    // listed as space separated id:worry pairs
+    pub fn rounds_to_csv(&self) -> String {
+        let mut s = String::from("round,monkey,inspections,items\n");
+        for round in &self.rounds {
+            for (monkey, (items, inspections)) in round.items.iter().zip(&round.inspections).enumerate() {
+                let items: Vec<String> = items.iter().map(|x| format!("{}:{}", x.id, x.worry)).collect();
+                s += &format!("{},{},{},{}\n", round.round, monkey, inspections, items.join(" "));
+            }
+        }
+        s
+    }
+
+    // Throws of a single item as CSV
+    pub fn trajectory_to_csv(&self, item: usize) -> String {
+        let mut s = String::from("item,round,from,to,worry\n");
+        for t in self.trajectory(item) {
+            s += &format!("{},{},{},{},{}\n", item, t.round, t.from, t.to, t.worry);
+        }
+        s
+    }
+}
+
+// Runs rounds while recording a snapshot after each round and every throw.
+// Monkeys are left in the same state as after `simulation::simulate`. On failure
+// every item is handed back, with the unprocessed ones still held by their monkey
+pub fn simulate_with_history<W: Worry>(monkeys: &mut [Monkey<W>], count: usize) -> Result<History<W>, WorryError> {
+    let mut next_id = 0;
+    let mut queues: Vec<VecDeque<Item<W>>> = monkeys.iter_mut()
+     .map(|m| m.items.drain(..)
+        .map(|worry| {
+            next_id += 1;
+            Item { id: next_id - 1, worry }
+        })
+        .collect())
+     .collect();
+
+    let start: Vec<Vec<Item<W>>> = queues.iter().map(|q| q.iter().cloned().collect()).collect();
+    let mut rounds = Vec::with_capacity(count);
+    let mut throws: Vec<Vec<Throw<W>>> = vec![Vec::new(); next_id];
+
+    let result = (1..=count).try_for_each(|round| {
+        let inspections = play_round(monkeys, &mut queues, &mut throws, round)?;
+        rounds.push(RoundSnapshot {
+            round,
+            items: queues.iter().map(|q| q.iter().cloned().collect()).collect(),
+            inspections,
+        });
+        Ok(())
+    });
+
+    for (monkey, queue) in monkeys.iter_mut().zip(queues) {
+        monkey.items = queue.into_iter().map(|x| x.worry).collect();
+    }
+    result.map(|_| History { start, rounds, throws })
+}
+
+// Plays a single round, returning the number of inspections made by each monkey
+fn play_round<W: Worry>(monkeys: &mut [Monkey<W>], queues: &mut [VecDeque<Item<W>>], throws: &mut [Vec<Throw<W>>], round: usize) -> Result<Vec<usize>, WorryError> {
+    let mut inspections = vec![0; monkeys.len()];
+    for i in 0..monkeys.len() {
+        // Items thrown back to the same monkey wait for its next turn
+        let mut held = std::mem::take(&mut queues[i]);
+        while let Some(item) = held.pop_front() {
+            let thrown = match monkeys[i].inspect(&item.worry) {
+                Ok((to, _)) if to >= monkeys.len() => Err(EvalError::UnknownMonkey(to)),
+                other => other
+            };
+            let (to, worry) = match thrown {
+                Ok(x) => x,
+                Err(error) => {
+                    held.push_front(item);
+                    held.append(&mut queues[i]);
+                    queues[i] = held;
+                    return Err(WorryError { round, monkey: i, error });
+                }
+            };
+            monkeys[i].activity += 1;
+            inspections[i] += 1;
+            throws[item.id].push(Throw { round, from: i, to, worry: worry.clone() });
+            queues[to].push_back(Item { id: item.id, worry });
+        }
+    }
+    Ok(inspections)
+}
+
+#[test]
+fn test_history_matches_simulation() {
+    let monkeys = crate::simulation::sample_monkeys();
+    let mut plain = monkeys.clone();
+    let activity = crate::simulation::simulate(&mut plain, 20).unwrap();
+    let mut tracked = monkeys;
+    let history = simulate_with_history(&mut tracked, 20).unwrap();
+    assert_eq!(history.total_inspections(), activity);
+    assert_eq!(tracked, plain);
+
+    // Worked example from the puzzle: after round 1 monkey 0 holds 20, 23, 27, 26
+    let held: Vec<u64> = history.rounds[0].items[0].iter().map(|x| x.worry).collect();
+    assert_eq!(held, vec![20, 23, 27, 26]);
+    assert_eq!(history.rounds[0].inspections, vec![2, 4, 3, 5]);
+
+    // Item 0 (79 held by monkey 0) goes to monkey 3 as 500, then to monkey 1 as 167
+    assert_eq!(history.item_count(), 10);
+    assert_eq!(&history.path(0)[..3], &[0, 3, 1]);
+    let csv = history.trajectory_to_csv(0);
+    assert!(csv.starts_with("item,round,from,to,worry\n0,1,0,3,500\n0,1,3,1,167\n"));
+    assert!(history.rounds_to_csv().contains("\n1,0,2,2:20 3:23 4:27 5:26\n"));
+}
+
+#[test]
+fn test_history_self_throw() {
+    // Monkey 0 keeps every item that fails its test
+    let mut monkeys = crate::simulation::sample_monkeys();
+    monkeys[0].b = 0;
+    crate::simulation::apply_common_modulus(&mut monkeys).unwrap();
+
+    let mut plain = monkeys.clone();
+    let activity = crate::simulation::simulate(&mut plain, 20).unwrap();
+    let mut tracked = monkeys;
+    let history = simulate_with_history(&mut tracked, 20).unwrap();
+    assert_eq!(history.total_inspections(), activity);
+    assert_eq!(tracked, plain);
+}
+
+#[test]
+fn test_history_failure_keeps_items() {
+    fn item_count(monkeys: &[Monkey]) -> usize {
+        monkeys.iter().map(|m| m.items.len()).sum()
+    }
+
+    // Monkey 1 throws to a monkey that does not exist
+    let mut monkeys = crate::simulation::sample_monkeys();
+    monkeys[1].b = 4;
+    let err = simulate_with_history(&mut monkeys, 20).unwrap_err();
+    assert_eq!(err, WorryError { round: 1, monkey: 1, error: EvalError::UnknownMonkey(4) });
+    assert_eq!(item_count(&monkeys), 10);
+    assert_eq!(monkeys[1].items.len(), 4);
+
+    // Monkey 2 cannot reduce its worry levels
+    let mut monkeys = crate::simulation::sample_monkeys();
+    monkeys[2].modulus = Some(0);
+    let err = simulate_with_history(&mut monkeys, 20).unwrap_err();
+    assert_eq!(err.error, EvalError::DivisionByZero);
+    assert_eq!(item_count(&monkeys), 10);
+}
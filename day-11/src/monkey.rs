@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+use std::fmt;
+use num::Num;
+
+use crate::expression::{self, Expr};
+use crate::worry::{EvalError, Worry};
+
+// A monkey holding items with worry levels of type W
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monkey<W = u64> {
+    pub items: VecDeque<W>,
+    pub operation: Expr,
+    pub divisor: u64,
+    pub a: usize,
+    pub b: usize,
+    pub modulus: Option<u64>,
+    pub activity: usize,
+}
+
+impl<W: Worry> Monkey<W> {
+    // Throw all items for a monkey, return a vec (next monkey, worry level)
+    // Fails if a worry level cannot be represented
+    pub fn take_turn(&mut self) -> Result<Vec<(usize, W)>, EvalError> {
+        let mut actions: Vec<(usize, W)> = Vec::new();
+        while let Some(item) = self.items.pop_front() {
+            self.activity += 1;
+            actions.push(self.inspect(&item)?);
+        }
+        Ok(actions)
+    }
+
+    // Inspect a single item, returning (next monkey, new worry level)
+    // Does not count towards the monkey's activity
+    pub fn inspect(&self, item: &W) -> Result<(usize, W), EvalError> {
+        let new_val = self.operation.eval(item)?.relieve(self.modulus)?;
+        if new_val.is_multiple_of(self.divisor) {
+            Ok((self.a, new_val))
+        } else {
+            Ok((self.b, new_val))
+        }
+    }
+
+    // Convert the monkey to hold a different worry level type
+    pub fn convert<V: Worry>(&self, f: impl Fn(&W) -> V) -> Monkey<V> {
+        Monkey {
+            items: self.items.iter().map(f).collect(),
+            operation: self.operation.clone(),
+            divisor: self.divisor,
+            a: self.a,
+            b: self.b,
+            modulus: self.modulus,
+            activity: self.activity,
+        }
+    }
+}
+
+// Errors produced while parsing a monkey description
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseMonkeyError {
+    // A required line is missing or does not start with the expected prefix
+    MissingField(&'static str),
+    // A numeric value could not be parsed
+    InvalidNumber(String),
+    // The divisibility test divides by zero
+    ZeroDivisor,
+    // The operation expression is not supported
+    Operation(expression::ParseError),
+}
+
+impl fmt::Display for ParseMonkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMonkeyError::MissingField(field) => write!(f, "missing '{}' line", field),
+            ParseMonkeyError::InvalidNumber(s)    => write!(f, "invalid number '{}'", s),
+            ParseMonkeyError::ZeroDivisor         => write!(f, "test divides by zero"),
+            ParseMonkeyError::Operation(e)        => write!(f, "invalid operation: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseMonkeyError {}
+
+// Parses a numeric value from a field of a monkey description
+fn parse_number<T>(value: &str) -> Result<T, ParseMonkeyError>
+where
+    T: Num + std::str::FromStr
+{
+    value.trim()
+     .parse::<T>()
+     .map_err(|_| ParseMonkeyError::InvalidNumber(value.to_string()))
+}
+
+// Strips the expected prefix from a line of a monkey description
+fn field<'a>(lines: &[&'a str], index: usize, prefix: &'static str) -> Result<&'a str, ParseMonkeyError> {
+    lines.get(index)
+     .and_then(|x| x.trim_start().strip_prefix(prefix))
+     .ok_or(ParseMonkeyError::MissingField(prefix))
+}
+
+// Parses a single monkey from text, with the operation stored as an expression tree
+pub fn parse_monkey(s: &str) -> Result<Monkey, ParseMonkeyError> {
+    let t: Vec<&str> = s.lines().collect();
+    let items = field(&t, 1, "Starting items: ")?
+     .split(", ")
+     .map(parse_number::<u64>)
+     .collect::<Result<VecDeque<u64>, _>>()?;
+
+    let operation = field(&t, 2, "Operation: new = ")?
+     .parse::<Expr>()
+     .map_err(ParseMonkeyError::Operation)?;
+
+    // Parse arguments to function
+    let div = parse_number::<u64>(field(&t, 3, "Test: divisible by ")?)?;
+    if div == 0 {
+        return Err(ParseMonkeyError::ZeroDivisor);
+    }
+    let a = parse_number::<usize>(field(&t, 4, "If true: throw to monkey ")?)?;
+    let b = parse_number::<usize>(field(&t, 5, "If false: throw to monkey ")?)?;
+
+    Ok(Monkey {
+        items,
+        operation,
+        divisor: div,
+        a,
+        b,
+        modulus: None,
+        activity: 0,
+    })
+}
+
+#[test]
+fn test_parse_monkey() {
+    let text = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by 23\n    If true: throw to monkey 2\n    If false: throw to monkey 3";
+    let monkey = parse_monkey(text).unwrap();
+    assert_eq!(monkey.items, VecDeque::from([79, 98]));
+    assert_eq!(monkey.operation.eval(&2u64), Ok(38));
+    assert_eq!((monkey.divisor, monkey.a, monkey.b), (23, 2, 3));
+
+    let bad = text.replace("old * 19", "old ** 19");
+    assert_eq!(parse_monkey(&bad), Err(ParseMonkeyError::Operation(expression::ParseError::UnexpectedToken("*".to_string()))));
+    assert_eq!(parse_monkey(&text.replace("by 23", "by 0")), Err(ParseMonkeyError::ZeroDivisor));
+    assert_eq!(parse_monkey("Monkey 0:"), Err(ParseMonkeyError::MissingField("Starting items: ")));
+}
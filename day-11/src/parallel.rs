@@ -0,0 +1,49 @@
+use rayon::prelude::*;
+
+use crate::cycles::item_round;
+use crate::monkey::Monkey;
+use crate::simulation::WorryError;
+use crate::worry::Worry;
+
+// Runs rounds with every item simulated independently on a thread pool and returns
+// the activity count for each monkey. Gives the same counts as `simulation::simulate`
+// but leaves the monkeys unmodified, since the final queue order is not reconstructed
+pub fn simulate<W>(monkeys: &[Monkey<W>], count: usize) -> Result<Vec<usize>, WorryError>
+where
+    W: Worry + Send + Sync
+{
+    let items: Vec<(usize, &W)> = monkeys.iter()
+     .enumerate()
+     .flat_map(|(i, m)| m.items.iter().map(move |x| (i, x)))
+     .collect();
+
+    let per_item = items.into_par_iter()
+     .map(|(monkey, worry)| {
+        let mut counts = vec![0; monkeys.len()];
+        let mut state = (monkey, worry.clone());
+        for round in 1..=count {
+            state = item_round(monkeys, round, state, &mut counts)?;
+        }
+        Ok(counts)
+     })
+     .collect::<Result<Vec<Vec<u64>>, WorryError>>()?;
+
+    let mut activity: Vec<usize> = monkeys.iter().map(|m| m.activity).collect();
+    for counts in per_item {
+        for (total, count) in activity.iter_mut().zip(counts) {
+            *total += count as usize;
+        }
+    }
+    Ok(activity)
+}
+
+#[test]
+fn test_parallel_matches_serial() {
+    let mut monkeys = crate::simulation::sample_monkeys();
+    let serial = crate::simulation::simulate(&mut monkeys.clone(), 20).unwrap();
+    assert_eq!(simulate(&monkeys, 20).unwrap(), serial);
+
+    crate::simulation::apply_common_modulus(&mut monkeys);
+    let serial = crate::simulation::simulate(&mut monkeys.clone(), 10000).unwrap();
+    assert_eq!(simulate(&monkeys, 10000).unwrap(), serial);
+}
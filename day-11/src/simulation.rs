@@ -0,0 +1,160 @@
+use std::fmt;
+use num::BigUint;
+
+use crate::monkey::Monkey;
+use crate::worry::{EvalError, Worry};
+
+// How worry levels are represented during a simulation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Arithmetic {
+    // 64 bit integers, failing on overflow
+    Checked,
+    // Arbitrary precision integers
+    BigInt,
+}
+
+// A worry level could not be computed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WorryError {
+    pub round: usize,
+    pub monkey: usize,
+    pub error: EvalError,
+}
+
+impl fmt::Display for WorryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "round {}, monkey {}: {}", self.round, self.monkey, self.error)
+    }
+}
+
+impl std::error::Error for WorryError {}
+
+// Smallest modulus that preserves every divisibility test (the lcm of the divisors).
+// Returns None if any operation does not commute with modular reduction, a divisor
+// is zero or the lcm does not fit in 64 bits
+pub fn common_modulus<W>(monkeys: &[Monkey<W>]) -> Option<u64> {
+    if !monkeys.iter().all(|m| m.operation.is_modular() && m.divisor > 0) {
+        return None;
+    }
+    monkeys.iter()
+     .map(|x| x.divisor)
+     .try_fold(1u64, |acc, d| {
+        let gcd = num::integer::gcd(acc, d);
+        (acc / gcd).checked_mul(d)
+     })
+}
+
+// Replace the relief step with reduction modulo the common modulus, if there is one.
+// Returns the modulus that was applied
+pub fn apply_common_modulus<W>(monkeys: &mut [Monkey<W>]) -> Option<u64> {
+    let modulus = common_modulus(monkeys)?;
+    for monkey in monkeys.iter_mut() {
+        monkey.modulus = Some(modulus);
+    }
+    Some(modulus)
+}
+
+// Runs rounds and returns the activity count for each monkey.
+// Fails if a worry level cannot be computed or an item is thrown to a missing monkey
+pub fn simulate<W: Worry>(monkeys: &mut [Monkey<W>], count: usize) -> Result<Vec<usize>, WorryError> {
+    for round in 0..count {
+        for i in 0..monkeys.len() {
+            let actions = monkeys[i].take_turn()
+             .map_err(|error| WorryError { round: round + 1, monkey: i, error })?;
+            for (to, value) in actions {
+                let target = monkeys.get_mut(to)
+                 .ok_or(WorryError { round: round + 1, monkey: i, error: EvalError::UnknownMonkey(to) })?;
+                target.items.push_back(value);
+            }
+        }
+    }
+    Ok(monkeys.iter().map(|x| x.activity).collect())
+}
+
+// Product of the two highest activity counts
+pub fn monkey_business(activity: &[usize]) -> usize {
+    let mut activity = activity.to_vec();
+    activity.sort_by(|a, b| b.cmp(a));
+    activity.iter().take(2).product()
+}
+
+// Runs rounds and returns the monkey business score
+pub fn get_monkey_business(monkeys: &[Monkey], count: usize, arithmetic: Arithmetic) -> Result<usize, WorryError> {
+    let activity = match arithmetic {
+        Arithmetic::Checked => simulate(&mut monkeys.to_vec(), count)?,
+        Arithmetic::BigInt  => {
+            let mut big: Vec<Monkey<BigUint>> = monkeys.iter()
+             .map(|m| m.convert(|&x| BigUint::from(x)))
+             .collect();
+            simulate(&mut big, count)?
+        }
+    };
+    Ok(monkey_business(&activity))
+}
+
+#[cfg(test)]
+pub(crate) fn sample_monkeys() -> Vec<Monkey> {
+    std::fs::read_to_string("sample.txt").unwrap()
+     .split("\n\n")
+     .map(|x| crate::monkey::parse_monkey(x).unwrap())
+     .collect()
+}
+
+#[test]
+fn test_sample_monkey_business() {
+    let mut monkeys = sample_monkeys();
+    assert_eq!(get_monkey_business(&monkeys, 20, Arithmetic::Checked), Ok(10605));
+    assert_eq!(get_monkey_business(&monkeys, 20, Arithmetic::BigInt), Ok(10605));
+
+    // Product of the divisors is 96577, which is also their lcm
+    assert_eq!(apply_common_modulus(&mut monkeys), Some(96577));
+    assert_eq!(get_monkey_business(&monkeys, 10000, Arithmetic::Checked), Ok(2713310158));
+}
+
+#[test]
+fn test_overflow_is_reported() {
+    // Without relief or a modulus, squaring overflows 64 bits within a few rounds
+    let mut monkeys = sample_monkeys();
+    for monkey in monkeys.iter_mut() {
+        monkey.modulus = Some(u64::MAX);
+    }
+    let err = get_monkey_business(&monkeys, 20, Arithmetic::Checked).unwrap_err();
+    assert_eq!(err.error, EvalError::Overflow(crate::expression::BinOp::Mul));
+    assert!(get_monkey_business(&monkeys, 5, Arithmetic::BigInt).is_ok());
+}
+
+#[test]
+fn test_common_modulus_uses_lcm() {
+    let mut monkeys = sample_monkeys();
+    for (monkey, divisor) in monkeys.iter_mut().zip([4, 6, 10, 15]) {
+        monkey.divisor = divisor;
+    }
+    assert_eq!(common_modulus(&monkeys), Some(60));
+    monkeys[0].operation = "old - 1".parse().unwrap();
+    assert_eq!(common_modulus(&monkeys), None);
+}
+
+#[test]
+fn test_zero_divisor_has_no_modulus() {
+    let mut monkeys = sample_monkeys();
+    monkeys[2].divisor = 0;
+    assert_eq!(common_modulus(&monkeys), None);
+    assert_eq!(apply_common_modulus(&mut monkeys), None);
+    assert!(monkeys.iter().all(|m| m.modulus.is_none()));
+}
+
+#[test]
+fn test_unknown_target_is_reported() {
+    let mut monkeys = sample_monkeys();
+    monkeys[1].b = 4;
+    let err = get_monkey_business(&monkeys, 20, Arithmetic::Checked).unwrap_err();
+    assert_eq!(err, WorryError { round: 1, monkey: 1, error: EvalError::UnknownMonkey(4) });
+}
+
+#[test]
+fn test_zero_modulus_is_reported() {
+    let mut monkeys = sample_monkeys();
+    monkeys[0].modulus = Some(0);
+    let err = get_monkey_business(&monkeys, 1, Arithmetic::BigInt).unwrap_err();
+    assert_eq!(err, WorryError { round: 1, monkey: 0, error: EvalError::DivisionByZero });
+}
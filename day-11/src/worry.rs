@@ -0,0 +1,111 @@
+use std::fmt;
+use num::{BigUint, Integer, Zero};
+
+use crate::expression::BinOp;
+
+// Errors produced while evaluating worry levels and throwing items
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EvalError {
+    // Result does not fit the worry level type
+    Overflow(BinOp),
+    // Subtraction would go below zero
+    Negative,
+    // Division by zero
+    DivisionByZero,
+    // Item thrown to a monkey that does not exist
+    UnknownMonkey(usize),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow(op)   => write!(f, "worry level overflowed in {:?}", op),
+            EvalError::Negative       => write!(f, "worry level went negative"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::UnknownMonkey(i) => write!(f, "item thrown to unknown monkey {}", i),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+// Numeric type that can hold an item's worry level
+pub trait Worry: Clone + fmt::Debug + fmt::Display + PartialEq {
+    fn from_u64(n: u64) -> Self;
+
+    // Apply a binary operator, failing instead of wrapping or panicking
+    fn apply(op: BinOp, lhs: Self, rhs: Self) -> Result<Self, EvalError>;
+
+    // Reduce the worry level after inspection, either modulo a common
+    // modulus or by the default relief of dividing by three. Fails on a zero modulus
+    fn relieve(self, modulus: Option<u64>) -> Result<Self, EvalError>;
+
+    fn is_multiple_of(&self, divisor: u64) -> bool;
+}
+
+impl Worry for u64 {
+    fn from_u64(n: u64) -> Self {
+        n
+    }
+
+    fn apply(op: BinOp, lhs: Self, rhs: Self) -> Result<Self, EvalError> {
+        match op {
+            BinOp::Add => lhs.checked_add(rhs).ok_or(EvalError::Overflow(op)),
+            BinOp::Sub => lhs.checked_sub(rhs).ok_or(EvalError::Negative),
+            BinOp::Mul => lhs.checked_mul(rhs).ok_or(EvalError::Overflow(op)),
+            BinOp::Div => lhs.checked_div(rhs).ok_or(EvalError::DivisionByZero),
+        }
+    }
+
+    fn relieve(self, modulus: Option<u64>) -> Result<Self, EvalError> {
+        match modulus {
+            Some(m) => self.checked_rem(m).ok_or(EvalError::DivisionByZero),
+            None    => Ok(self / 3)
+        }
+    }
+
+    fn is_multiple_of(&self, divisor: u64) -> bool {
+        u64::is_multiple_of(*self, divisor)
+    }
+}
+
+impl Worry for BigUint {
+    fn from_u64(n: u64) -> Self {
+        BigUint::from(n)
+    }
+
+    fn apply(op: BinOp, lhs: Self, rhs: Self) -> Result<Self, EvalError> {
+        match op {
+            BinOp::Add => Ok(lhs + rhs),
+            BinOp::Sub if lhs < rhs => Err(EvalError::Negative),
+            BinOp::Sub => Ok(lhs - rhs),
+            BinOp::Mul => Ok(lhs * rhs),
+            BinOp::Div if rhs.is_zero() => Err(EvalError::DivisionByZero),
+            BinOp::Div => Ok(lhs / rhs),
+        }
+    }
+
+    fn relieve(self, modulus: Option<u64>) -> Result<Self, EvalError> {
+        match modulus {
+            Some(0) => Err(EvalError::DivisionByZero),
+            Some(m) => Ok(self % m),
+            None    => Ok(self / 3u32)
+        }
+    }
+
+    fn is_multiple_of(&self, divisor: u64) -> bool {
+        Integer::is_multiple_of(self, &BigUint::from(divisor))
+    }
+}
+
+#[test]
+fn test_checked_arithmetic() {
+    assert_eq!(u64::apply(BinOp::Mul, u64::MAX / 2, 3), Err(EvalError::Overflow(BinOp::Mul)));
+    assert_eq!(u64::apply(BinOp::Sub, 2, 3), Err(EvalError::Negative));
+    assert_eq!(u64::apply(BinOp::Div, 2, 0), Err(EvalError::DivisionByZero));
+    let big = BigUint::apply(BinOp::Mul, BigUint::from(u64::MAX), BigUint::from(4u32)).unwrap();
+    assert!(Worry::is_multiple_of(&big, 4));
+    assert_eq!(big.clone().relieve(Some(1 << 20)), Ok(BigUint::from((1u64 << 20) - 4)));
+    assert_eq!(big.relieve(Some(0)), Err(EvalError::DivisionByZero));
+    assert_eq!(7u64.relieve(Some(0)), Err(EvalError::DivisionByZero));
+}
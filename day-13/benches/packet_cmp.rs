@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_13::packet::{FlatPacket, Packet, PacketArena};
+
+fn load_packets() -> Vec<Packet> {
+    std::fs::read_to_string("input.txt").unwrap()
+     .lines()
+     .filter(|x| !x.is_empty())
+     .map(|x| x.parse().unwrap())
+     .collect()
+}
+
+// Sorting every packet from the input with each representation
+fn bench_sort(c: &mut Criterion) {
+    let packets = load_packets();
+    let flat: Vec<FlatPacket> = packets.iter().map(FlatPacket::from).collect();
+    let mut arena = PacketArena::new();
+    let ids: Vec<_> = packets.iter().map(|p| arena.push(p)).collect();
+
+    let mut group = c.benchmark_group("sort");
+    group.bench_function("tree", |b| b.iter(|| {
+        let mut v = packets.clone();
+        v.sort();
+        v
+    }));
+    group.bench_function("flat", |b| b.iter(|| {
+        let mut v = flat.clone();
+        v.sort();
+        v
+    }));
+    group.bench_function("arena", |b| b.iter(|| {
+        let mut v = ids.clone();
+        arena.sort(&mut v);
+        v
+    }));
+    group.finish();
+}
+
+// Comparing each consecutive pair of packets, as in part one
+fn bench_pairs(c: &mut Criterion) {
+    let packets = load_packets();
+    let flat: Vec<FlatPacket> = packets.iter().map(FlatPacket::from).collect();
+
+    let mut group = c.benchmark_group("pairs");
+    group.bench_function("tree", |b| b.iter(|| {
+        packets.chunks(2).filter(|p| p[0] < p[1]).count()
+    }));
+    group.bench_function("flat", |b| b.iter(|| {
+        flat.chunks(2).filter(|p| p[0] < p[1]).count()
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, bench_sort, bench_pairs);
+criterion_main!(benches);
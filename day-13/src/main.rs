@@ -1,5 +1,4 @@
-pub mod packet;
-use packet::Packet;
+use day_13::packet::Packet;
 
 fn main() {
     let packet_pairs = std::fs::read_to_string("input.txt")
@@ -7,7 +6,7 @@ fn main() {
         .split("\n\n")
         .map(|x| {
             x.split("\n")
-                .map(|y| parse_packet(y))
+                .map(|y| y.parse::<Packet>().unwrap())
                 .collect::<Vec<Packet>>()
         })
         .map(|packets| (packets[0].clone(), packets[1].clone()))
@@ -36,85 +35,3 @@ fn main() {
     let i2 = packets.iter().zip(1..).find(|(x, _)| *x == &div_b).unwrap();
     println!("Part two: {}", i1.1 * i2.1);
 }
-
-// Gets the next valid packet sequence out of the current list
-fn get_next_packet_string(s: &str) -> String {
-    let mut n = 1;
-    let mut str = Vec::new();
-    for c in s.chars() {
-        str.push(c);
-        match c {
-            '[' => {
-                n += 1;
-            }
-            ']' => {
-                n -= 1;
-                if n == 0 {
-                    return str.into_iter().collect();
-                }
-            }
-            _ => (),
-        }
-    }
-    str.into_iter().collect()
-}
-
-
-// Parses a string into a packet 
-fn parse_packet(s: &str) -> Packet {
-    let (_, p) = parse_inner(s);
-    if p.len() > 1 {
-        Packet::List(p)
-    } else {
-        p[0].clone()
-    }
-}
-
-// Parses an inner packet, returns tuple of: num characters parsed, and the Packet parsed
-fn parse_inner(s: &str) -> (usize, Vec<Packet>) {
-    let mut p = Vec::new();
-    let mut str = Vec::new();
-    let mut count = 0;
-    let mut iter = s.chars();
-    while let Some(c) = iter.next() {
-        count += 1;
-        match c {
-            '[' => {
-                let from = s.chars().skip(count).collect::<String>();
-                let new = get_next_packet_string(&from);
-                let (used, packets) = parse_inner(new.as_str());
-                p.push(Packet::List(packets));
-                for _ in 0..used + 1 {
-                    iter.next();
-                    count += 1;
-                }
-                str = Vec::new();
-            }
-            ']' => (),
-            ',' => {
-                if str.len() > 0 {
-                    let new: String = str.iter().collect();
-                    let (_, packets) = parse_inner(new.as_str());
-                    for packet in packets {
-                        p.push(packet);
-                    }
-                }
-                str = Vec::new();
-            }
-            _ => str.push(c),
-        }
-    }
-
-    if str.len() > 0 {
-        let items = str.iter().collect::<String>();
-        let packets = items
-            .split(",")
-            .flat_map(|x| x.parse::<i32>().ok())
-            .map(|x| Packet::Int(x))
-            .collect::<Vec<Packet>>();
-        for packet in packets {
-            p.push(packet);
-        }
-    }
-    return (count, p);
-}
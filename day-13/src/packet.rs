@@ -1,4 +1,14 @@
 use std::fmt;
+use std::str::FromStr;
+
+pub use self::explain::{Explanation, Reason, Side, Step};
+pub use self::flat::{FlatPacket, PacketArena, PacketId};
+mod explain;
+mod flat;
+#[cfg(feature = "serde")]
+pub use self::json::load_packets;
+#[cfg(feature = "serde")]
+mod json;
 
 // Packet is either an int, or a list of packets
 #[derive(Clone)]
@@ -7,29 +17,147 @@ pub enum Packet {
     List(Vec<Packet>),
 }
 
-// Custom debug print trait implementation for debugging
-impl fmt::Debug for Packet {
+// Errors produced while parsing a packet
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsePacketError {
+    // Input ended inside a list or before any value
+    UnexpectedEnd,
+    // Character that is not valid at this position (byte offset)
+    UnexpectedChar(char, usize),
+    // Integer that does not fit in an i32 or is not written in its shortest
+    // form (leading zeros, `-0`), starting at this position
+    InvalidInteger(String, usize),
+    // Input continues after a complete packet, starting at this position
+    TrailingCharacters(usize),
+}
+
+impl fmt::Display for ParsePacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePacketError::UnexpectedEnd          => write!(f, "unexpected end of packet"),
+            ParsePacketError::UnexpectedChar(c, i)   => write!(f, "unexpected '{}' at position {}", c, i),
+            ParsePacketError::InvalidInteger(s, i)   => write!(f, "invalid integer '{}' at position {}", s, i),
+            ParsePacketError::TrailingCharacters(i)  => write!(f, "unexpected characters after packet at position {}", i),
+        }
+    }
+}
+
+impl std::error::Error for ParsePacketError {}
+
+// Single pass recursive descent parser over the bytes of a packet
+struct Parser<'a> {
+    s: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.position..].chars().next()
+    }
+
+    fn unexpected(&self) -> ParsePacketError {
+        match self.peek() {
+            Some(c) => ParsePacketError::UnexpectedChar(c, self.position),
+            None    => ParsePacketError::UnexpectedEnd,
+        }
+    }
+
+    // Parse either a list or an integer
+    fn value(&mut self) -> Result<Packet, ParsePacketError> {
+        match self.peek() {
+            Some('[') => self.list(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.integer(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    // Parse a bracketed, comma separated list of values
+    fn list(&mut self) -> Result<Packet, ParsePacketError> {
+        self.position += 1;
+        let mut items = Vec::new();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Packet::List(items));
+        }
+        loop {
+            items.push(self.value()?);
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    // Parse an optionally negative integer. Only the form Display writes is
+    // accepted, so `007` and `-0` are rejected
+    fn integer(&mut self) -> Result<Packet, ParsePacketError> {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        let digits = self.s[self.position..].bytes().take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return Err(self.unexpected());
+        }
+        self.position += digits;
+        let text = &self.s[start..self.position];
+        let magnitude = text.trim_start_matches('-');
+        if magnitude.starts_with('0') && text != "0" {
+            return Err(ParsePacketError::InvalidInteger(text.to_string(), start));
+        }
+        text.parse::<i32>()
+         .map(Packet::Int)
+         .map_err(|_| ParsePacketError::InvalidInteger(text.to_string(), start))
+    }
+}
+
+// Parse a packet from its text form, e.g. `[1,[2,3],[]]`
+impl FromStr for Packet {
+    type Err = ParsePacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { s, position: 0 };
+        let packet = parser.value()?;
+        if parser.position < s.len() {
+            return Err(ParsePacketError::TrailingCharacters(parser.position));
+        }
+        Ok(packet)
+    }
+}
+
+// Write a packet in the same text form it is parsed from
+impl fmt::Display for Packet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
-            Packet::Int(x) => write!(f, "{}", x)?,
+            Packet::Int(x) => write!(f, "{}", x),
             Packet::List(l) => {
                 write!(f, "[")?;
-                for i in 0..l.len() {
-                    if i < l.len() - 1 {
-                        write!(f, "{:?},", l[i])?;
-                    } else {
-                        write!(f, "{:?}", l[i])?;
+                for (i, p) in l.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
                     }
+                    write!(f, "{}", p)?;
                 }
-                write!(f, "]")?;
+                write!(f, "]")
             }
         }
-        Ok(())
+    }
+}
+
+// Custom debug print trait implementation for debugging
+impl fmt::Debug for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
     }
 }
 
 // Custom implmentation of PartialEq trait for the main puzzle logic
 impl PartialEq for Packet {
+    #[allow(clippy::map_all_any_identity)]
     fn eq(&self, other: &Self) -> bool {
         match &self {
             Packet::Int(x) => match other {
@@ -61,6 +189,7 @@ impl PartialOrd for Packet {
 
 // Implement Ord trait to compare packets based on challenge rules
 impl Ord for Packet {
+    #[allow(clippy::needless_borrow)]
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match &self {
             Packet::Int(x) => match other {
@@ -103,6 +232,7 @@ fn test_equality_comparison() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_less_comparison() {
     assert_ne!(
         Packet::List(vec![Packet::Int(7), Packet::Int(7)]) < Packet::List(vec![Packet::Int(7)]),
@@ -115,3 +245,57 @@ fn test_less_comparison() {
     let l2 = Packet::List(vec![Packet::List(vec![Packet::Int(1)]), Packet::Int(4)]);
     assert_eq!(l1 < l2, true);
 }
+
+#[test]
+fn test_parse_packet() {
+    let p: Packet = "[[1],[2,3,4]]".parse().unwrap();
+    let expected = Packet::List(vec![
+        Packet::List(vec![Packet::Int(1)]),
+        Packet::List(vec![Packet::Int(2), Packet::Int(3), Packet::Int(4)]),
+    ]);
+    assert_eq!(p, expected);
+    assert_eq!("[[1]]".parse::<Packet>().unwrap(), Packet::List(vec![Packet::List(vec![Packet::Int(1)])]));
+    assert_eq!("[]".parse::<Packet>().unwrap(), Packet::List(vec![]));
+    assert_eq!("10".parse::<Packet>().unwrap(), Packet::Int(10));
+    assert_eq!(expected.to_string(), "[[1],[2,3,4]]");
+}
+
+#[test]
+fn test_parse_packet_errors() {
+    assert_eq!("[1,2".parse::<Packet>(), Err(ParsePacketError::UnexpectedEnd));
+    assert_eq!("[1,,2]".parse::<Packet>(), Err(ParsePacketError::UnexpectedChar(',', 3)));
+    assert_eq!("[1]]".parse::<Packet>(), Err(ParsePacketError::TrailingCharacters(3)));
+    assert_eq!("[1 ]".parse::<Packet>(), Err(ParsePacketError::UnexpectedChar(' ', 2)));
+    assert_eq!("[-]".parse::<Packet>(), Err(ParsePacketError::UnexpectedChar(']', 2)));
+    assert_eq!("[99999999999]".parse::<Packet>(), Err(ParsePacketError::InvalidInteger("99999999999".to_string(), 1)));
+    assert_eq!("[1,007]".parse::<Packet>(), Err(ParsePacketError::InvalidInteger("007".to_string(), 3)));
+    assert_eq!("[-0]".parse::<Packet>(), Err(ParsePacketError::InvalidInteger("-0".to_string(), 1)));
+    assert_eq!("[0,-10]".parse::<Packet>().unwrap().to_string(), "[0,-10]");
+    assert_eq!("".parse::<Packet>(), Err(ParsePacketError::UnexpectedEnd));
+}
+
+#[cfg(test)]
+fn arbitrary_packet() -> impl proptest::strategy::Strategy<Value = Packet> {
+    use proptest::prelude::*;
+    any::<i32>().prop_map(Packet::Int).prop_recursive(6, 64, 8, |inner| {
+        proptest::collection::vec(inner, 0..8).prop_map(Packet::List)
+    })
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_display_round_trip(p in arbitrary_packet()) {
+        let text = p.to_string();
+        let parsed: Packet = text.parse().unwrap();
+        proptest::prop_assert_eq!(&parsed, &p);
+        proptest::prop_assert_eq!(parsed.to_string(), text);
+    }
+
+    #[test]
+    fn test_parse_never_panics(s in "[\\[\\],0-9-]{0,24}") {
+        if let Ok(p) = s.parse::<Packet>() {
+            proptest::prop_assert_eq!(p.to_string(), s);
+        }
+    }
+}
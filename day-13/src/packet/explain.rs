@@ -0,0 +1,161 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::Packet;
+
+// Which side of a comparison a step refers to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+// Why a comparison was decided
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Reason {
+    LeftSmaller,
+    RightSmaller,
+    LeftRanOut,
+    RightRanOut,
+}
+
+// A single step taken while comparing two packets
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    // Two values were compared. Index is their position in the enclosing lists,
+    // or None for the top level packets and values promoted from an integer
+    Compare { index: Option<usize>, left: Packet, right: Packet },
+    // An integer on one side was wrapped in a list to compare against a list
+    Promote { side: Side, promoted: Packet },
+    // The comparison was decided
+    Decided(Reason),
+}
+
+// The full path taken by a comparison. Each step is paired with its nesting depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub ordering: Ordering,
+    pub steps: Vec<(usize, Step)>,
+}
+
+impl Packet {
+    // Compare two packets while recording every step taken. The resulting ordering
+    // always matches `Ord::cmp`
+    pub fn explain_cmp(&self, other: &Packet) -> Explanation {
+        let mut steps = Vec::new();
+        let ordering = explain(self, other, 0, None, &mut steps);
+        Explanation { ordering, steps }
+    }
+}
+
+fn explain(left: &Packet, right: &Packet, depth: usize, index: Option<usize>, steps: &mut Vec<(usize, Step)>) -> Ordering {
+    steps.push((depth, Step::Compare { index, left: left.clone(), right: right.clone() }));
+    match (left, right) {
+        (Packet::Int(x), Packet::Int(y)) => {
+            let ordering = x.cmp(y);
+            match ordering {
+                Ordering::Less    => steps.push((depth + 1, Step::Decided(Reason::LeftSmaller))),
+                Ordering::Greater => steps.push((depth + 1, Step::Decided(Reason::RightSmaller))),
+                Ordering::Equal   => (),
+            }
+            ordering
+        },
+        (Packet::Int(x), Packet::List(_)) => {
+            let promoted = Packet::List(vec![Packet::Int(*x)]);
+            steps.push((depth + 1, Step::Promote { side: Side::Left, promoted: promoted.clone() }));
+            explain(&promoted, right, depth + 1, None, steps)
+        },
+        (Packet::List(_), Packet::Int(y)) => {
+            let promoted = Packet::List(vec![Packet::Int(*y)]);
+            steps.push((depth + 1, Step::Promote { side: Side::Right, promoted: promoted.clone() }));
+            explain(left, &promoted, depth + 1, None, steps)
+        },
+        (Packet::List(x), Packet::List(y)) => {
+            for i in 0.. {
+                match (x.get(i), y.get(i)) {
+                    (Some(x_val), Some(y_val)) => {
+                        let ordering = explain(x_val, y_val, depth + 1, Some(i), steps);
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    },
+                    (None, Some(_)) => {
+                        steps.push((depth + 1, Step::Decided(Reason::LeftRanOut)));
+                        return Ordering::Less;
+                    },
+                    (Some(_), None) => {
+                        steps.push((depth + 1, Step::Decided(Reason::RightRanOut)));
+                        return Ordering::Greater;
+                    },
+                    (None, None) => break,
+                }
+            }
+            Ordering::Equal
+        },
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Compare { left, right, .. } => write!(f, "Compare {} vs {}", left, right),
+            Step::Promote { side, promoted } => {
+                let side = if *side == Side::Left { "left" } else { "right" };
+                write!(f, "Mixed types; convert {} to {} and retry comparison", side, promoted)
+            },
+            Step::Decided(reason) => match reason {
+                Reason::LeftSmaller  => write!(f, "Left side is smaller, so inputs are in the right order"),
+                Reason::RightSmaller => write!(f, "Right side is smaller, so inputs are not in the right order"),
+                Reason::LeftRanOut   => write!(f, "Left side ran out of items, so inputs are in the right order"),
+                Reason::RightRanOut  => write!(f, "Right side ran out of items, so inputs are not in the right order"),
+            },
+        }
+    }
+}
+
+// Render in the style of the puzzle walkthrough, one indented bullet per step
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (depth, step) in &self.steps {
+            writeln!(f, "{}- {}", "  ".repeat(*depth), step)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_explain_walkthrough() {
+    let left: Packet = "[[1],[2,3,4]]".parse().unwrap();
+    let right: Packet = "[[1],4]".parse().unwrap();
+    let explanation = left.explain_cmp(&right);
+    assert_eq!(explanation.ordering, Ordering::Less);
+    assert_eq!(explanation.to_string(), "\
+- Compare [[1],[2,3,4]] vs [[1],4]
+  - Compare [1] vs [1]
+    - Compare 1 vs 1
+  - Compare [2,3,4] vs 4
+    - Mixed types; convert right to [4] and retry comparison
+    - Compare [2,3,4] vs [4]
+      - Compare 2 vs 4
+        - Left side is smaller, so inputs are in the right order
+");
+    assert_eq!(explanation.steps[3].1, Step::Compare { index: Some(1), left: "[2,3,4]".parse().unwrap(), right: Packet::Int(4) });
+
+    let explanation = "[7,7,7,7]".parse::<Packet>().unwrap().explain_cmp(&"[7,7,7]".parse().unwrap());
+    assert_eq!(explanation.ordering, Ordering::Greater);
+    assert_eq!(explanation.steps.last(), Some(&(1, Step::Decided(Reason::RightRanOut))));
+}
+
+#[test]
+fn test_explain_matches_ordering() {
+    let packets: Vec<Packet> = std::fs::read_to_string("sample.txt").unwrap()
+     .lines()
+     .filter(|x| !x.is_empty())
+     .map(|x| x.parse().unwrap())
+     .collect();
+    for a in &packets {
+        for b in &packets {
+            assert_eq!(a.explain_cmp(b).ordering, a.cmp(b));
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Packet, ParsePacketError};
+
+// A node of a flattened packet. Nodes are stored in pre-order, so the children
+// of a list directly follow it and `span` (the size of the subtree including
+// the list itself) skips to its next sibling
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Node {
+    Int(i32),
+    List { len: u32, span: u32 },
+}
+
+// Number of nodes in the subtree starting at index i
+fn span(nodes: &[Node], i: usize) -> usize {
+    match nodes[i] {
+        Node::Int(_)            => 1,
+        Node::List { span, .. } => span as usize,
+    }
+}
+
+// Append a packet to a node buffer in pre-order
+fn flatten(packet: &Packet, nodes: &mut Vec<Node>) {
+    match packet {
+        Packet::Int(x) => nodes.push(Node::Int(*x)),
+        Packet::List(l) => {
+            let start = nodes.len();
+            nodes.push(Node::List { len: l.len() as u32, span: 0 });
+            for p in l {
+                flatten(p, nodes);
+            }
+            nodes[start] = Node::List { len: l.len() as u32, span: (nodes.len() - start) as u32 };
+        }
+    }
+}
+
+// Rebuild the tree form of the subtree starting at index i
+fn unflatten(nodes: &[Node], i: usize) -> Packet {
+    match nodes[i] {
+        Node::Int(x) => Packet::Int(x),
+        Node::List { len, .. } => {
+            let mut items = Vec::with_capacity(len as usize);
+            let mut child = i + 1;
+            for _ in 0..len {
+                items.push(unflatten(nodes, child));
+                child += span(nodes, child);
+            }
+            Packet::List(items)
+        }
+    }
+}
+
+// Compare the subtree at a[i] with the subtree at b[j] without allocating.
+// Integers compared against lists are treated as single item lists in place
+fn cmp_nodes(a: &[Node], i: usize, b: &[Node], j: usize) -> Ordering {
+    match (a[i], b[j]) {
+        (Node::Int(x), Node::Int(y)) => x.cmp(&y),
+        (Node::Int(_), Node::List { len, .. }) => cmp_promoted(a, i, b, j, len),
+        (Node::List { len, .. }, Node::Int(_)) => cmp_promoted(b, j, a, i, len).reverse(),
+        (Node::List { len: len_a, .. }, Node::List { len: len_b, .. }) => {
+            let (mut ci, mut cj) = (i + 1, j + 1);
+            for _ in 0..len_a.min(len_b) {
+                let ordering = cmp_nodes(a, ci, b, cj);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                ci += span(a, ci);
+                cj += span(b, cj);
+            }
+            len_a.cmp(&len_b)
+        }
+    }
+}
+
+// Compare the integer at a[i], viewed as a one item list, with the list at b[j]
+fn cmp_promoted(a: &[Node], i: usize, b: &[Node], j: usize, len: u32) -> Ordering {
+    if len == 0 {
+        return Ordering::Greater;
+    }
+    match cmp_nodes(a, i, b, j + 1) {
+        Ordering::Equal => 1.cmp(&len),
+        ordering        => ordering,
+    }
+}
+
+// Packet stored as a single contiguous buffer of nodes
+#[derive(Clone, PartialEq)]
+pub struct FlatPacket {
+    nodes: Vec<Node>,
+}
+
+impl From<&Packet> for FlatPacket {
+    fn from(packet: &Packet) -> Self {
+        let mut nodes = Vec::new();
+        flatten(packet, &mut nodes);
+        Self { nodes }
+    }
+}
+
+impl From<&FlatPacket> for Packet {
+    fn from(packet: &FlatPacket) -> Self {
+        unflatten(&packet.nodes, 0)
+    }
+}
+
+impl FromStr for FlatPacket {
+    type Err = ParsePacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(FlatPacket::from(&s.parse::<Packet>()?))
+    }
+}
+
+impl fmt::Display for FlatPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Packet::from(self))
+    }
+}
+
+impl fmt::Debug for FlatPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Eq for FlatPacket {}
+
+impl PartialOrd for FlatPacket {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FlatPacket {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_nodes(&self.nodes, 0, &other.nodes, 0)
+    }
+}
+
+// Handle to a packet stored in a PacketArena
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PacketId(usize);
+
+// Many packets stored back to back in one shared node buffer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PacketArena {
+    nodes: Vec<Node>,
+}
+
+impl PacketArena {
+    // Create an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Add a packet to the arena
+    pub fn push(&mut self, packet: &Packet) -> PacketId {
+        let id = PacketId(self.nodes.len());
+        flatten(packet, &mut self.nodes);
+        id
+    }
+
+    // Parse a packet into the arena
+    pub fn parse(&mut self, s: &str) -> Result<PacketId, ParsePacketError> {
+        Ok(self.push(&s.parse()?))
+    }
+
+    // Rebuild the tree form of a packet
+    pub fn get(&self, id: PacketId) -> Packet {
+        unflatten(&self.nodes, id.0)
+    }
+
+    // Compare two packets in the arena
+    pub fn cmp(&self, a: PacketId, b: PacketId) -> Ordering {
+        cmp_nodes(&self.nodes, a.0, &self.nodes, b.0)
+    }
+
+    // Sort packet handles into packet order
+    pub fn sort(&self, ids: &mut [PacketId]) {
+        ids.sort_by(|&a, &b| self.cmp(a, b));
+    }
+}
+
+#[test]
+fn test_flat_comparison() {
+    let pairs = [
+        ("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less),
+        ("[[1],[2,3,4]]", "[[1],4]", Ordering::Less),
+        ("[9]", "[[8,7,6]]", Ordering::Greater),
+        ("[[4,4],4,4]", "[[4,4],4,4,4]", Ordering::Less),
+        ("[7,7,7,7]", "[7,7,7]", Ordering::Greater),
+        ("[]", "[3]", Ordering::Less),
+        ("[[[]]]", "[[]]", Ordering::Greater),
+        ("[1,[2,[3,[4,[5,6,7]]]],8,9]", "[1,[2,[3,[4,[5,6,0]]]],8,9]", Ordering::Greater),
+        ("[[2]]", "[2]", Ordering::Equal),
+    ];
+    let mut arena = PacketArena::new();
+    for (a, b, expected) in pairs {
+        let (fa, fb) = (a.parse::<FlatPacket>().unwrap(), b.parse::<FlatPacket>().unwrap());
+        assert_eq!(fa.cmp(&fb), expected, "{} vs {}", a, b);
+        let (ia, ib) = (arena.parse(a).unwrap(), arena.parse(b).unwrap());
+        assert_eq!(arena.cmp(ia, ib), expected);
+        assert_eq!(arena.get(ia).to_string(), a);
+    }
+}
+
+#[cfg(test)]
+fn small_packet() -> impl proptest::strategy::Strategy<Value = Packet> {
+    use proptest::prelude::*;
+    // Small values so that equal prefixes are common
+    (0..4).prop_map(Packet::Int).prop_recursive(4, 32, 4, |inner| {
+        proptest::collection::vec(inner, 0..4).prop_map(Packet::List)
+    })
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_flat_matches_tree(a in small_packet(), b in small_packet()) {
+        let (fa, fb) = (FlatPacket::from(&a), FlatPacket::from(&b));
+        proptest::prop_assert_eq!(fa.cmp(&fb), a.cmp(&b));
+        proptest::prop_assert_eq!(&Packet::from(&fa), &a);
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use super::Packet;
+
+// Packets map directly onto JSON: integers become numbers and lists become arrays
+impl Serialize for Packet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Packet::Int(x) => serializer.serialize_i32(*x),
+            Packet::List(l) => {
+                let mut seq = serializer.serialize_seq(Some(l.len()))?;
+                for p in l {
+                    seq.serialize_element(p)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+struct PacketVisitor;
+
+impl<'de> Visitor<'de> for PacketVisitor {
+    type Value = Packet;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an i32 or an array of packets")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Packet, E> {
+        i32::try_from(v)
+         .map(Packet::Int)
+         .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Packet, E> {
+        i32::try_from(v)
+         .map(Packet::Int)
+         .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Packet, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(p) = seq.next_element()? {
+            items.push(p);
+        }
+        Ok(Packet::List(items))
+    }
+}
+
+impl<'de> Deserialize<'de> for Packet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PacketVisitor)
+    }
+}
+
+// Load a list of packets from a JSON file containing an array of packets
+pub fn load_packets(path: impl AsRef<Path>) -> Result<Vec<Packet>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+#[test]
+fn test_json_round_trip() {
+    let p: Packet = "[[1],[2,3,4],[],-5]".parse().unwrap();
+    let json = serde_json::to_string(&p).unwrap();
+    assert_eq!(json, "[[1],[2,3,4],[],-5]");
+    assert_eq!(serde_json::from_str::<Packet>(&json).unwrap(), p);
+    assert_eq!(serde_json::from_str::<Packet>(" [ 1 , [ 2 ] ] ").unwrap().to_string(), "[1,[2]]");
+}
+
+#[test]
+fn test_json_rejects_non_packets() {
+    assert!(serde_json::from_str::<Packet>("[1.5]").is_err());
+    assert!(serde_json::from_str::<Packet>("[\"1\"]").is_err());
+    assert!(serde_json::from_str::<Packet>("[4294967296]").is_err());
+}
+
+#[test]
+fn test_load_packets() {
+    let path = std::env::temp_dir().join(format!("day-13-packets-{}.json", std::process::id()));
+    std::fs::write(&path, "[[1,1,3,1,1], [1,1,5,1,1], 7]").unwrap();
+    let packets = load_packets(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(packets.len(), 3);
+    assert!(packets[0] < packets[1]);
+}
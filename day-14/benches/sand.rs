@@ -0,0 +1,38 @@
+use std::collections::HashSet;
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_14::{cave, solver};
+
+fn load_rocks() -> HashSet<(i32, i32)> {
+    cave::parse_scan(&std::fs::read_to_string("input.txt").unwrap())
+}
+
+// Filling the cave until sand falls into the abyss
+fn bench_abyss(c: &mut Criterion) {
+    let rocks = load_rocks();
+    let bottom = rocks.iter().map(|(_, y)| *y).max().unwrap();
+
+    let mut group = c.benchmark_group("abyss");
+    group.bench_function("restart", |b| b.iter(|| {
+        solver::simulate(&rocks, bottom + 1, |(_, y)| y >= bottom)
+    }));
+    group.bench_function("stack", |b| b.iter(|| solver::simulate_stack(&rocks, false)));
+    group.finish();
+}
+
+// Filling the cave up to the source with the floor
+fn bench_floor(c: &mut Criterion) {
+    let rocks = load_rocks();
+    let bottom = rocks.iter().map(|(_, y)| *y).max().unwrap();
+
+    let mut group = c.benchmark_group("floor");
+    group.sample_size(10);
+    group.bench_function("restart", |b| b.iter(|| {
+        solver::simulate(&rocks, bottom + 2, |point| point == (500, 0))
+    }));
+    group.bench_function("stack", |b| b.iter(|| solver::simulate_stack(&rocks, true)));
+    group.bench_function("reachable", |b| b.iter(|| solver::reachable_area(&rocks)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_abyss, bench_floor);
+criterion_main!(benches);
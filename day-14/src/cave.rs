@@ -0,0 +1,352 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use itertools::Itertools;
+
+pub use self::config::{Abyss, ConfigError, Floor, SandConfig};
+mod config;
+
+// Build the set of rock cells from the scan, one path of points per line
+pub fn parse_scan(s: &str) -> HashSet<(i32, i32)> {
+    let mut rocks = HashSet::new();
+    for line in s.lines() {
+        let points: Vec<(i32, i32)> = line.split(" -> ")
+         .flat_map(|x| utilities::parse_pair::<i32>(x, ","))
+         .collect();
+        for (from, to) in points.iter().tuple_windows() {
+            for x in from.0.min(to.0)..=from.0.max(to.0) {
+                for y in from.1.min(to.1)..=from.1.max(to.1) {
+                    rocks.insert((x, y));
+                }
+            }
+        }
+    }
+    rocks
+}
+
+// Contents of an occupied cell
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Rock,
+    Sand,
+}
+
+// Where a dropped grain ended up
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Settled((i32, i32)),
+    // Reached the abyss
+    Abyss,
+    // Every source is already covered
+    Blocked,
+}
+
+// Cave slice holding rock and settled sand
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cave {
+    cells: HashMap<(i32, i32), Cell>,
+    config: SandConfig,
+    // Lowest rock row
+    bottom: i32,
+    // Source the next grain is taken from
+    next_source: usize,
+    // Cells the most recent grain passed through
+    path: Vec<(i32, i32)>,
+    grains: usize,
+}
+
+impl Cave {
+    // Create a cave from a set of rock cells with the puzzle's rules
+    pub fn new(rocks: &HashSet<(i32, i32)>) -> Self {
+        Self::build(rocks, SandConfig::default())
+    }
+
+    // Create a cave from a set of rock cells with custom rules. Fails if the
+    // rules are not valid
+    pub fn with_config(rocks: &HashSet<(i32, i32)>, config: SandConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self::build(rocks, config))
+    }
+
+    fn build(rocks: &HashSet<(i32, i32)>, config: SandConfig) -> Self {
+        Self {
+            cells: rocks.iter().map(|&p| (p, Cell::Rock)).collect(),
+            config,
+            bottom: rocks.iter().map(|(_, y)| *y).max().unwrap_or(0),
+            next_source: 0,
+            path: Vec::new(),
+            grains: 0,
+        }
+    }
+
+    // Add an infinite floor two rows below the lowest rock
+    pub fn with_floor(mut self) -> Self {
+        self.config.floor = Floor::Infinite(2);
+        self
+    }
+
+    pub fn get_config(&self) -> &SandConfig {
+        &self.config
+    }
+
+    // Row of the floor, if there is one
+    fn floor_row(&self) -> Option<i32> {
+        match self.config.floor {
+            Floor::None                 => None,
+            Floor::Infinite(depth)      => Some(self.bottom + depth),
+            Floor::Finite { depth, .. } => Some(self.bottom + depth),
+        }
+    }
+
+    // Row at which a falling grain is lost
+    fn abyss_row(&self) -> i32 {
+        match self.config.abyss {
+            Abyss::Lowest => self.floor_row().map_or(self.bottom, |f| f.max(self.bottom)),
+            Abyss::Row(y) => y,
+        }
+    }
+
+    // Number of grains that have settled
+    pub fn grains(&self) -> usize {
+        self.grains
+    }
+
+    // Contents of a cell, with the floor counted as rock
+    pub fn get(&self, (x, y): (i32, i32)) -> Option<Cell> {
+        match self.config.floor {
+            Floor::Infinite(depth) if y >= self.bottom + depth => Some(Cell::Rock),
+            Floor::Finite { depth, min_x, max_x }
+                if y == self.bottom + depth && (min_x..=max_x).contains(&x) => Some(Cell::Rock),
+            _ => self.cells.get(&(x, y)).copied(),
+        }
+    }
+
+    // Next source that is not covered, taking sources in turn
+    fn take_source(&mut self) -> Option<(i32, i32)> {
+        let sources = &self.config.sources;
+        let i = (0..sources.len())
+         .map(|k| (self.next_source + k) % sources.len())
+         .find(|&i| self.get(sources[i]).is_none())?;
+        self.next_source = (i + 1) % sources.len();
+        Some(sources[i])
+    }
+
+    // Cells the most recent grain passed through, including where it stopped
+    pub fn get_path(&self) -> &[(i32, i32)] {
+        &self.path
+    }
+
+    // Drop a single grain from the next source and let it fall until it settles
+    // or reaches the abyss
+    pub fn drop_grain(&mut self) -> Outcome {
+        let Some(mut s) = self.take_source() else {
+            return Outcome::Blocked;
+        };
+        let abyss = self.abyss_row();
+        self.path.clear();
+        self.path.push(s);
+        loop {
+            let next = self.config.directions.iter()
+             .map(|d| (s.0 + d.0, s.1 + d.1))
+             .find(|&p| self.get(p).is_none());
+            match next {
+                Some(p) => s = p,
+                None => {
+                    self.cells.insert(s, Cell::Sand);
+                    self.grains += 1;
+                    return Outcome::Settled(s);
+                }
+            }
+            self.path.push(s);
+            if s.1 >= abyss {
+                return Outcome::Abyss;
+            }
+        }
+    }
+
+    // Drop grains until one reaches the abyss or every source is covered.
+    // Returns the number of settled grains
+    pub fn fill(&mut self) -> usize {
+        while let Outcome::Settled(_) = self.drop_grain() {}
+        self.grains
+    }
+
+    // Fill the cave, rendering a frame after every `every` grains and once
+    // more at the end
+    pub fn fill_with_frames(&mut self, every: usize) -> Vec<String> {
+        let every = every.max(1);
+        let mut frames = Vec::new();
+        while let Outcome::Settled(_) = self.drop_grain() {
+            if self.grains.is_multiple_of(every) {
+                frames.push(self.render());
+            }
+        }
+        frames.push(self.render());
+        frames
+    }
+
+    // Fill the cave and write the frames as numbered text files into a directory.
+    // Returns the number of frames written
+    pub fn dump_frames(&mut self, dir: impl AsRef<Path>, every: usize) -> io::Result<usize> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let frames = self.fill_with_frames(every);
+        for (i, frame) in frames.iter().enumerate() {
+            fs::write(dir.join(format!("frame_{:04}.txt", i)), frame)?;
+        }
+        Ok(frames.len())
+    }
+
+    // Draw the cave as ASCII. Rock is `#`, sand `o`, sources `+` and the
+    // path of the most recent grain `~`
+    pub fn render(&self) -> String {
+        let floor_ends = match self.config.floor {
+            Floor::Finite { min_x, max_x, .. } => vec![min_x, max_x],
+            _ => Vec::new(),
+        };
+        let (min_x, max_x) = self.cells.keys().chain(&self.path).chain(&self.config.sources)
+         .map(|p| p.0)
+         .chain(floor_ends)
+         .minmax()
+         .into_option()
+         .unwrap_or((0, 0));
+        let sources = &self.config.sources;
+        let min_y = sources.iter().map(|p| p.1).chain([0]).min().unwrap();
+        let max_y = sources.iter().map(|p| p.1).chain(self.floor_row()).chain([self.bottom]).max().unwrap();
+        let path: HashSet<&(i32, i32)> = self.path.iter().collect();
+
+        let mut s = String::new();
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                s.push(match self.get((x, y)) {
+                    Some(Cell::Rock)                  => '#',
+                    Some(Cell::Sand)                  => 'o',
+                    None if sources.contains(&(x, y)) => '+',
+                    None if path.contains(&(x, y))    => '~',
+                    None                              => '.',
+                });
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+fn sample_cave() -> Cave {
+    Cave::new(&parse_scan(&std::fs::read_to_string("sample.txt").unwrap()))
+}
+
+#[test]
+fn test_fill() {
+    assert_eq!(sample_cave().fill(), 24);
+    assert_eq!(sample_cave().with_floor().fill(), 93);
+}
+
+#[test]
+fn test_render() {
+    let mut cave = sample_cave();
+    for _ in 0..5 {
+        cave.drop_grain();
+    }
+    assert_eq!(cave.render(), concat!(
+        "......+...\n",
+        "......~...\n",
+        "......~...\n",
+        "......~...\n",
+        "....#.~.##\n",
+        "....#.~.#.\n",
+        "..###.~.#.\n",
+        ".....~o.#.\n",
+        "....oooo#.\n",
+        "#########.\n",
+    ));
+
+    // Final state from the puzzle with the path of the grain that falls out
+    cave.fill();
+    assert_eq!(cave.drop_grain(), Outcome::Abyss);
+    assert_eq!(cave.render(), concat!(
+        ".......+...\n",
+        ".......~...\n",
+        "......~o...\n",
+        ".....~ooo..\n",
+        "....~#ooo##\n",
+        "...~o#ooo#.\n",
+        "..~###ooo#.\n",
+        "..~..oooo#.\n",
+        ".~o.ooooo#.\n",
+        "~#########.\n",
+    ));
+}
+
+#[test]
+fn test_frames() {
+    let mut cave = sample_cave();
+    let frames = cave.fill_with_frames(10);
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].matches('o').count(), 10);
+    assert_eq!(frames[2].matches('o').count(), 24);
+}
+
+#[test]
+fn test_multiple_sources() {
+    let rocks = parse_scan("490,5 -> 510,5");
+    let config = SandConfig { sources: vec![(495, 0), (505, 0)], ..SandConfig::default() };
+    let mut cave = Cave::with_config(&rocks, config).unwrap();
+    assert_eq!(cave.drop_grain(), Outcome::Settled((495, 4)));
+    assert_eq!(cave.drop_grain(), Outcome::Settled((505, 4)));
+    // Both piles grow until they cover their source
+    assert_eq!(cave.fill(), 2 * 25);
+    assert_eq!(cave.drop_grain(), Outcome::Blocked);
+}
+
+#[test]
+fn test_custom_directions() {
+    // Preferring right over left gives the mirror image of the sample
+    let mirrored: HashSet<(i32, i32)> = parse_scan(&std::fs::read_to_string("sample.txt").unwrap())
+     .into_iter()
+     .map(|(x, y)| (1000 - x, y))
+     .collect();
+    let config = SandConfig { directions: vec![(0, 1), (1, 1), (-1, 1)], ..SandConfig::default() };
+    let mut cave = Cave::with_config(&mirrored, config).unwrap();
+    assert_eq!(cave.fill(), 24);
+    let lines: Vec<String> = cave.render().lines().map(|x| x.chars().rev().collect()).collect();
+    let mut original = sample_cave();
+    original.fill();
+    original.drop_grain();
+    assert_eq!(lines.join("\n") + "\n", original.render());
+}
+
+#[test]
+fn test_floors_and_abyss() {
+    // A finite floor catches some of the sand that falls off the rock
+    let config = SandConfig {
+        floor: Floor::Finite { depth: 2, min_x: 490, max_x: 506 },
+        ..SandConfig::default()
+    };
+    let mut cave = Cave::with_config(&parse_scan(&std::fs::read_to_string("sample.txt").unwrap()), config).unwrap();
+    assert_eq!(cave.fill(), 44);
+    // The grain that is lost falls past the left end of the floor
+    assert!(cave.render().ends_with("\n~#################\n"));
+
+    // Losing grains on reaching the row of the rock ledges stops immediately
+    let config = SandConfig { abyss: Abyss::Row(4), ..SandConfig::with_floor() };
+    let mut cave = Cave::with_config(&parse_scan(&std::fs::read_to_string("sample.txt").unwrap()), config).unwrap();
+    assert_eq!(cave.fill(), 0);
+}
+
+#[test]
+fn test_invalid_config() {
+    let rocks = parse_scan("490,5 -> 510,5");
+    let config = SandConfig { directions: vec![(0, 1), (1, 0)], ..SandConfig::default() };
+    assert_eq!(Cave::with_config(&rocks, config), Err(ConfigError::NotFalling((1, 0))));
+    let config = SandConfig { directions: Vec::new(), ..SandConfig::default() };
+    assert_eq!(Cave::with_config(&rocks, config), Err(ConfigError::NoDirections));
+    let config = SandConfig { sources: Vec::new(), ..SandConfig::default() };
+    assert_eq!(Cave::with_config(&rocks, config).unwrap_err().to_string(), "no sand sources");
+    let config = SandConfig { floor: Floor::Infinite(-1), ..SandConfig::default() };
+    assert_eq!(Cave::with_config(&rocks, config), Err(ConfigError::NegativeDepth(-1)));
+    let config = SandConfig { floor: Floor::Finite { depth: -3, min_x: 490, max_x: 510 }, ..SandConfig::default() };
+    assert_eq!(Cave::with_config(&rocks, config), Err(ConfigError::NegativeDepth(-3)));
+}
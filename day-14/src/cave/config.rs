@@ -0,0 +1,95 @@
+use std::fmt;
+
+// Floor below the rock scan. Depths are rows below the lowest rock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Floor {
+    None,
+    // Floor extending forever in both directions
+    Infinite(i32),
+    // Floor covering the columns min_x..=max_x, sand can fall off its ends
+    Finite { depth: i32, min_x: i32, max_x: i32 },
+}
+
+// When a falling grain counts as lost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Abyss {
+    // On reaching the lowest row of rock or floor, below which nothing can stop it
+    Lowest,
+    // On reaching a given row
+    Row(i32),
+}
+
+// Rules for the sand simulation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandConfig {
+    // Points sand pours from. Grains are taken from each source in turn and a
+    // source stops once it is covered
+    pub sources: Vec<(i32, i32)>,
+    // Offsets a grain tries to move by, in order of preference. Every offset
+    // must move the grain down
+    pub directions: Vec<(i32, i32)>,
+    pub floor: Floor,
+    pub abyss: Abyss,
+}
+
+impl Default for SandConfig {
+    // Rules from the puzzle: one source at (500, 0), falling down, then down and
+    // left, then down and right, with no floor
+    fn default() -> Self {
+        Self {
+            sources: vec![(500, 0)],
+            directions: vec![(0, 1), (-1, 1), (1, 1)],
+            floor: Floor::None,
+            abyss: Abyss::Lowest,
+        }
+    }
+}
+
+impl SandConfig {
+    // Rules for part two, with an infinite floor two rows below the lowest rock
+    pub fn with_floor() -> Self {
+        Self { floor: Floor::Infinite(2), ..Self::default() }
+    }
+
+    // Check that sand can be poured, that every grain keeps falling so that a
+    // simulation always finishes, and that any floor is not above the lowest rock
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.sources.is_empty() {
+            return Err(ConfigError::NoSources);
+        }
+        if self.directions.is_empty() {
+            return Err(ConfigError::NoDirections);
+        }
+        if let Some(&d) = self.directions.iter().find(|d| d.1 <= 0) {
+            return Err(ConfigError::NotFalling(d));
+        }
+        match self.floor {
+            Floor::Infinite(depth) | Floor::Finite { depth, .. } if depth < 0 => Err(ConfigError::NegativeDepth(depth)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Reasons a sand configuration cannot be simulated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    NoSources,
+    NoDirections,
+    // A direction that does not move the grain down
+    NotFalling((i32, i32)),
+    // A floor depth above the lowest rock
+    NegativeDepth(i32),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoSources        => write!(f, "no sand sources"),
+            ConfigError::NoDirections     => write!(f, "no fall directions"),
+            ConfigError::NotFalling(d)    => write!(f, "fall direction {:?} does not move down", d),
+            ConfigError::NegativeDepth(d) => write!(f, "floor depth {} is above the lowest rock", d),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
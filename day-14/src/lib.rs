@@ -0,0 +1,3 @@
+extern crate utilities;
+pub mod cave;
+pub mod solver;
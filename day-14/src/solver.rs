@@ -0,0 +1,96 @@
+use std::collections::{HashSet, VecDeque};
+
+// Sand falling simulation
+pub fn simulate<F>(scan: &HashSet<(i32, i32)>, bottom: i32, exit: F) -> usize
+where F: Fn((i32, i32)) -> bool
+{
+    let mut filled = scan.clone();
+    let start = (500, 0);
+    'outer: loop {
+        let mut s = start;
+        loop {
+            let falling_to = vec![(s.0, s.1 + 1), (s.0 - 1, s.1 + 1), (s.0 + 1, s.1 + 1)];
+            let mut next_pos = s;
+            for next in falling_to {
+                if !filled.contains(&next) && next.1 < bottom {
+                    next_pos = next;
+                    break;
+                }
+            }
+            if next_pos == s {
+                filled.insert(s);
+                break;
+            } else {
+                s = next_pos;
+            }
+            // Check exit condition in inner loop
+            if exit(s) {
+                break 'outer;
+            }
+        }
+        // Check exit condition from outer loop
+        if exit(s) {
+            break;
+        }
+    }
+    filled.len() - scan.len()
+}
+
+// Positions a grain at `s` can fall to, in order of preference
+fn falling_to(s: (i32, i32)) -> [(i32, i32); 3] {
+    [(s.0, s.1 + 1), (s.0 - 1, s.1 + 1), (s.0 + 1, s.1 + 1)]
+}
+
+// Sand falling simulation that keeps the path of the current grain on a stack.
+// When a grain settles the next one follows the same path up to that point, so
+// it resumes from the last open position instead of restarting at the source.
+// With `floor` set the cave has a floor two rows below the lowest rock and the
+// simulation runs until the source is covered, otherwise until a grain falls
+// past the lowest rock
+pub fn simulate_stack(scan: &HashSet<(i32, i32)>, floor: bool) -> usize {
+    let bottom = scan.iter().map(|(_, y)| *y).max().unwrap_or(0);
+    let mut filled = scan.clone();
+    let mut path = vec![(500, 0)];
+    let mut count = 0;
+    while let Some(&s) = path.last() {
+        match falling_to(s).into_iter().find(|p| !filled.contains(p) && (!floor || p.1 < bottom + 2)) {
+            Some(next) if !floor && next.1 >= bottom => break,
+            Some(next) => path.push(next),
+            None => {
+                filled.insert(s);
+                path.pop();
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Number of grains that settle with a floor two rows below the lowest rock.
+// Sand ends up filling every cell it can reach from the source, so instead of
+// simulating grains this counts the cells reachable with a breadth first search
+pub fn reachable_area(scan: &HashSet<(i32, i32)>) -> usize {
+    let floor = scan.iter().map(|(_, y)| *y).max().unwrap_or(0) + 2;
+    let mut seen = HashSet::from([(500, 0)]);
+    let mut queue = VecDeque::from([(500, 0)]);
+    while let Some(s) = queue.pop_front() {
+        for next in falling_to(s) {
+            if next.1 < floor && !scan.contains(&next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+#[test]
+fn test_solvers_on_sample() {
+    let rocks = crate::cave::parse_scan(&std::fs::read_to_string("sample.txt").unwrap());
+    let bottom = rocks.iter().map(|(_, y)| *y).max().unwrap();
+    let part_one = simulate(&rocks, bottom + 1, |(_, y)| y >= bottom);
+    let part_two = simulate(&rocks, bottom + 2, |point| point == (500, 0));
+    assert_eq!((part_one, part_two), (24, 93));
+    assert_eq!(simulate_stack(&rocks, false), part_one);
+    assert_eq!(simulate_stack(&rocks, true), part_two);
+    assert_eq!(reachable_area(&rocks), part_two);
+}
@@ -0,0 +1,5 @@
+pub mod map;
+pub mod region;
+pub mod rotated;
+pub mod rows;
+pub mod sensor;
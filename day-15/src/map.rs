@@ -0,0 +1,148 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use utilities::IntervalSet;
+
+use crate::region::{row_coverage, Rect};
+use crate::sensor::SensorPoint;
+
+// Most rows of a block used to estimate its coverage. Blocks with more rows
+// than this are sampled at evenly spaced rows
+const SAMPLE_ROWS: i32 = 8;
+
+// What a block of the map shows
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Tile {
+    Sensor,
+    Beacon,
+    // Fraction of the block within range of a sensor
+    Coverage(f64),
+}
+
+// Downscaled map of the sensors, beacons and coverage in a rectangle, where
+// each tile stands for a block of scale × scale spaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageMap {
+    rect: Rect,
+    scale: i32,
+    tiles: Vec<Vec<Tile>>,
+}
+
+impl CoverageMap {
+    pub fn new(pairs: &[SensorPoint], rect: Rect, scale: i32) -> Self {
+        let scale = scale.max(1);
+        let (columns, rows) = ((rect.width() + scale - 1) / scale, (rect.height() + scale - 1) / scale);
+        let block = |i: i32, min: i32, max: i32| (min + i * scale, (min + (i + 1) * scale - 1).min(max));
+
+        let mut tiles = Vec::with_capacity(rows as usize);
+        for r in 0..rows {
+            let (top, bottom) = block(r, rect.min.1, rect.max.1);
+            let height = bottom - top + 1;
+            let sampled: Vec<i32> = if height <= SAMPLE_ROWS {
+                (top..=bottom).collect()
+            } else {
+                (0..SAMPLE_ROWS).map(|k| top + (2 * k + 1) * height / (2 * SAMPLE_ROWS)).collect()
+            };
+            let coverage: Vec<IntervalSet> = sampled.iter().map(|&y| row_coverage(pairs, &rect, y)).collect();
+            tiles.push((0..columns)
+             .map(|c| {
+                let (left, right) = block(c, rect.min.0, rect.max.0);
+                let cells = IntervalSet::from_interval(left as i64, right as i64);
+                let covered: u128 = coverage.iter().map(|row| row.intersect(&cells).len()).sum();
+                Tile::Coverage(covered as f64 / (cells.len() * sampled.len() as u128) as f64)
+             })
+             .collect::<Vec<_>>());
+        }
+
+        let mut map = Self { rect, scale, tiles };
+        for p in pairs {
+            map.mark(p.b, Tile::Beacon);
+        }
+        for p in pairs {
+            map.mark(p.s, Tile::Sensor);
+        }
+        map
+    }
+
+    // Replace the tile holding a space
+    fn mark(&mut self, p: (i32, i32), tile: Tile) {
+        if self.rect.contains(&p) {
+            let (c, r) = ((p.0 - self.rect.min.0) / self.scale, (p.1 - self.rect.min.1) / self.scale);
+            self.tiles[r as usize][c as usize] = tile;
+        }
+    }
+
+    pub fn get_tiles(&self) -> &[Vec<Tile>] {
+        &self.tiles
+    }
+
+    // Draw the map as ASCII. Sensors are `S`, beacons `B`, fully covered
+    // blocks `#`, partly covered blocks `+` and blocks with no coverage `.`
+    pub fn to_ascii(&self) -> String {
+        self.tiles.iter()
+         .map(|row| row.iter()
+            .map(|tile| match tile {
+                Tile::Sensor                   => 'S',
+                Tile::Beacon                   => 'B',
+                Tile::Coverage(f) if *f >= 1.0 => '#',
+                Tile::Coverage(f) if *f > 0.0  => '+',
+                Tile::Coverage(_)              => '.',
+            })
+            .collect::<String>() + "\n")
+         .collect()
+    }
+
+    // Encode the map as a plain PGM image. Uncovered space is black so that
+    // gaps stand out, with coverage shaded grey and sensors and beacons light
+    pub fn to_pgm(&self) -> String {
+        let body: String = self.tiles.iter()
+         .map(|row| row.iter().map(|&t| level(t).to_string()).collect::<Vec<_>>().join(" ") + "\n")
+         .collect();
+        let width = self.tiles.first().map_or(0, |r| r.len());
+        format!("P2\n{} {}\n255\n{}", width, self.tiles.len(), body)
+    }
+
+    pub fn save_pgm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_pgm())
+    }
+}
+
+fn level(tile: Tile) -> u8 {
+    match tile {
+        Tile::Sensor      => 255,
+        Tile::Beacon      => 220,
+        Tile::Coverage(f) => (f * 160.0).round() as u8,
+    }
+}
+
+#[cfg(test)]
+use crate::sensor::sample_pairs;
+
+#[test]
+fn test_full_scale_map() {
+    let pairs = sample_pairs();
+    let map = CoverageMap::new(&pairs, Rect::new((0, 0), (20, 20)), 1);
+    let ascii = map.to_ascii();
+    let lines: Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 21);
+    assert_eq!(lines[11].chars().nth(14), Some('.'));
+    assert_eq!(ascii.matches('.').count(), 1);
+    assert_eq!(ascii.matches('S').count(), 14);
+    // The beacon at (2, 10) is the closest to two sensors
+    assert_eq!(&lines[10][..4], "##B#");
+}
+
+#[test]
+fn test_downscaled_map() {
+    let pairs = sample_pairs();
+    let map = CoverageMap::new(&pairs, Rect::new((0, 0), (20, 20)), 7);
+    assert_eq!(map.get_tiles().len(), 3);
+    assert!(map.to_pgm().starts_with("P2\n3 3\n255\n"));
+
+    // Blocks without markers report the fraction of covered spaces
+    let rect = Rect::bounding(&pairs).unwrap();
+    let map = CoverageMap::new(&pairs, rect, 1000);
+    assert_eq!(map.get_tiles(), &[vec![Tile::Sensor]]);
+    let map = CoverageMap::new(&pairs, Rect::new((100, 100), (109, 109)), 5);
+    assert_eq!(map.to_ascii(), "..\n..\n");
+}
@@ -0,0 +1,96 @@
+use utilities::IntervalSet;
+
+use crate::rows::get_coverage_in_row;
+use crate::sensor::SensorPoint;
+
+// Rectangle of spaces with inclusive corners
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rect {
+    pub min: (i32, i32),
+    pub max: (i32, i32),
+}
+
+impl Rect {
+    pub fn new(min: (i32, i32), max: (i32, i32)) -> Self {
+        Self { min, max }
+    }
+
+    // Smallest rectangle holding every space any sensor covers
+    pub fn bounding(pairs: &[SensorPoint]) -> Option<Self> {
+        let min_x = pairs.iter().map(|p| p.s.0 - p.db).min()?;
+        let max_x = pairs.iter().map(|p| p.s.0 + p.db).max()?;
+        let min_y = pairs.iter().map(|p| p.s.1 - p.db).min()?;
+        let max_y = pairs.iter().map(|p| p.s.1 + p.db).max()?;
+        Some(Self::new((min_x, min_y), (max_x, max_y)))
+    }
+
+    pub fn width(&self) -> i32 {
+        (self.max.0 - self.min.0 + 1).max(0)
+    }
+
+    pub fn height(&self) -> i32 {
+        (self.max.1 - self.min.1 + 1).max(0)
+    }
+
+    // Number of spaces in the rectangle
+    pub fn area(&self) -> u64 {
+        self.width() as u64 * self.height() as u64
+    }
+
+    pub fn contains(&self, p: &(i32, i32)) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0) && (self.min.1..=self.max.1).contains(&p.1)
+    }
+
+    // Columns of the rectangle
+    fn columns(&self) -> IntervalSet {
+        IntervalSet::from_interval(self.min.0 as i64, self.max.0 as i64)
+    }
+}
+
+// Spaces in one row of the rectangle within range of at least one sensor
+pub fn row_coverage(pairs: &[SensorPoint], rect: &Rect, row: i32) -> IntervalSet {
+    get_coverage_in_row(pairs, row).intersect(&rect.columns())
+}
+
+// Number of spaces in the rectangle within range of at least one sensor,
+// counting the spaces holding sensors and beacons
+pub fn covered_area(pairs: &[SensorPoint], rect: &Rect) -> u64 {
+    (rect.min.1..=rect.max.1)
+     // A row of the rectangle holds at most width spaces, which fits in a u64
+     .map(|y| row_coverage(pairs, rect, y).len() as u64)
+     .sum()
+}
+
+// Every space in the rectangle that no sensor covers, in row order. The
+// result can be as large as the rectangle itself
+pub fn uncovered_cells(pairs: &[SensorPoint], rect: &Rect) -> Vec<(i32, i32)> {
+    let columns = rect.columns();
+    (rect.min.1..=rect.max.1)
+     .flat_map(|y| {
+        columns.subtract(&get_coverage_in_row(pairs, y)).intervals().iter()
+         .flat_map(|&(l, u)| l..=u)
+         .map(|x| (x as i32, y))
+         .collect::<Vec<_>>()
+     })
+     .collect()
+}
+
+#[cfg(test)]
+use crate::sensor::sample_pairs;
+
+#[test]
+fn test_region_queries() {
+    let pairs = sample_pairs();
+    let rect = Rect::new((0, 0), (20, 20));
+    assert_eq!(uncovered_cells(&pairs, &rect), vec![(14, 11)]);
+    assert_eq!(covered_area(&pairs, &rect), rect.area() - 1);
+
+    // Check against testing every space of a rectangle over the edge of the coverage
+    let rect = Rect::new((-12, -15), (30, 5));
+    let uncovered: Vec<(i32, i32)> = (rect.min.1..=rect.max.1)
+     .flat_map(|y| (rect.min.0..=rect.max.0).map(move |x| (x, y)))
+     .filter(|p| !pairs.iter().any(|s| s.covers(p)))
+     .collect();
+    assert_eq!(uncovered_cells(&pairs, &rect), uncovered);
+    assert_eq!(covered_area(&pairs, &rect), rect.area() - uncovered.len() as u64);
+}
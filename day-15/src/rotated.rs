@@ -0,0 +1,91 @@
+use utilities::IntervalSet;
+
+use crate::sensor::SensorPoint;
+
+// In rotated coordinates u = x + y, v = x - y the area a sensor covers is an
+// axis aligned square, so each sensor is stored as a centre and radius
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Square {
+    u: i64,
+    v: i64,
+    r: i64,
+}
+
+impl From<&SensorPoint> for Square {
+    fn from(p: &SensorPoint) -> Self {
+        let (x, y) = (p.s.0 as i64, p.s.1 as i64);
+        Self { u: x + y, v: x - y, r: p.db as i64 }
+    }
+}
+
+// Smallest and largest x with 2x inside lower..=upper
+fn halve(lower: i64, upper: i64) -> (i64, i64) {
+    (-(-lower).div_euclid(2), upper.div_euclid(2))
+}
+
+// Spaces on the diagonal x + y = c (or x - y = c when `sum` is false) that lie
+// within min..=max on both axes and are not covered by any square
+fn uncovered_on_line(squares: &[Square], c: i64, sum: bool, min: i64, max: i64) -> Option<(i64, i64)> {
+    let y = |x: i64| if sum { c - x } else { x - c };
+    let (lower, upper) = if sum { (min.max(c - max), max.min(c - min)) } else { (min.max(min + c), max.min(max + c)) };
+    let covered: IntervalSet = squares.iter()
+     .filter_map(|s| {
+        // Position of the square along the line and across it
+        let (along, across) = if sum { (s.v, s.u) } else { (s.u, s.v) };
+        ((c - across).abs() <= s.r).then(|| halve(c + along - s.r, c + along + s.r))
+     })
+     .collect();
+    let (x, _) = IntervalSet::from_interval(lower, upper).subtract(&covered).bounds()?;
+    Some((x, y(x)))
+}
+
+// Find a space within min..=max on both axes that no sensor covers. If the
+// space has a covered neighbour it is one step outside that sensor's square,
+// which puts it on a diagonal line next to one of the square's edges. Only
+// those lines are searched, so the cost depends on the number of sensors
+// rather than the size of the area
+pub fn find_uncovered(pairs: &[SensorPoint], min: i32, max: i32) -> Option<(i32, i32)> {
+    let squares: Vec<Square> = pairs.iter().map(Square::from).collect();
+    let (min, max) = (min as i64, max as i64);
+    let mut lines: Vec<(i64, bool)> = squares.iter()
+     .flat_map(|s| [(s.u - s.r - 1, true), (s.u + s.r + 1, true), (s.v - s.r - 1, false), (s.v + s.r + 1, false)])
+     .collect();
+    // Uncovered spaces that do not touch any coverage fill the whole area,
+    // so the corner is checked as well
+    lines.push((min + min, true));
+    lines.sort();
+    lines.dedup();
+    lines.into_iter()
+     .find_map(|(c, sum)| uncovered_on_line(&squares, c, sum, min, max))
+     .map(|(x, y)| (x as i32, y as i32))
+}
+
+#[cfg(test)]
+use crate::sensor::sample_pairs;
+
+#[test]
+fn test_matches_row_scan() {
+    let pairs = sample_pairs();
+    assert_eq!(find_uncovered(&pairs, 0, 20), Some((14, 11)));
+    assert_eq!(find_uncovered(&pairs, 0, 20), crate::rows::find_uncovered(&pairs, 0, 20));
+}
+
+#[test]
+fn test_small_areas() {
+    // Compare against checking every space for areas all over the sample
+    let pairs = sample_pairs();
+    let covered = |x: i32, y: i32| pairs.iter().any(|p| SensorPoint::get_distance(&p.s, &(x, y)) <= p.db);
+    for size in [0, 1, 3] {
+        for min in -10..=25 {
+            let max = min + size;
+            let expected = (min..=max).any(|y| (min..=max).any(|x| !covered(x, y)));
+            match find_uncovered(&pairs, min, max) {
+                Some((x, y)) => {
+                    assert!((min..=max).contains(&x) && (min..=max).contains(&y));
+                    assert!(!covered(x, y));
+                },
+                None => assert!(!expected, "missed a space in {}..={}", min, max),
+            }
+        }
+    }
+}
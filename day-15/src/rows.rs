@@ -0,0 +1,36 @@
+use utilities::IntervalSet;
+
+use crate::sensor::SensorPoint;
+
+// Spaces in a row within range of at least one sensor
+pub fn get_coverage_in_row(pairs: &[SensorPoint], row: i32) -> IntervalSet {
+    pairs.iter()
+     .flat_map(|x| x.intersect_at_row(row))
+     .map(|(l, u)| (l as i64, u as i64))
+     .collect()
+}
+
+// Spaces in a row where a beacon cannot be, excluding the known beacons
+pub fn get_spaces_covered_in_row(pairs: &[SensorPoint], row: i32) -> IntervalSet {
+    let mut covered = get_coverage_in_row(pairs, row);
+    for point in pairs.iter().filter(|p| p.b.1 == row) {
+        covered.remove(point.b.0 as i64, point.b.0 as i64);
+    }
+    covered
+}
+
+// Find a space within min..=max on both axes that no sensor covers, by
+// scanning each row in turn
+pub fn find_uncovered(pairs: &[SensorPoint], min: i32, max: i32) -> Option<(i32, i32)> {
+    let area = IntervalSet::from_interval(min as i64, max as i64);
+    (min..=max).find_map(|y| {
+        area.subtract(&get_coverage_in_row(pairs, y)).bounds().map(|(x, _)| (x as i32, y))
+    })
+}
+
+#[test]
+fn test_sample_rows() {
+    let pairs = crate::sensor::sample_pairs();
+    assert_eq!(get_spaces_covered_in_row(&pairs, 10).len(), 26);
+    assert_eq!(find_uncovered(&pairs, 0, 20), Some((14, 11)));
+}
@@ -0,0 +1,67 @@
+use regex::Regex;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SensorPoint {
+    // Sensor position
+    pub s: (i32, i32),
+    // Closest beacon
+    pub b: (i32, i32),
+    // Distance to the closest beacon
+    pub db: i32,
+}
+
+impl SensorPoint {
+    // Build point from vector
+    #[allow(clippy::ptr_arg, clippy::redundant_field_names)]
+    pub fn from_vec(v: &Vec<i32>) -> Option<Self> {
+        if v.len() < 4 {
+            None
+        } else {
+            let s = (v[0], v[1]);
+            let e = (v[2], v[3]);
+            Some(
+                Self {
+                    s: s,
+                    b: e,
+                    db: SensorPoint::get_distance(&s, &e)
+                }
+            )
+        }        
+    }
+
+    // Returns a tuple of min/max coordinates at each row intersection
+    pub fn intersect_at_row(&self, r: i32) -> Option<(i32, i32)> {                
+        let delta = SensorPoint::get_distance(&self.s, &(self.s.0, r));
+        if delta > self.db {
+            return None;
+        }                
+        Some((self.s.0 - (self.db - delta), self.s.0 + (self.db - delta)))
+    }
+
+    // Check if a space is within range of the sensor
+    pub fn covers(&self, p: &(i32, i32)) -> bool {
+        SensorPoint::get_distance(&self.s, p) <= self.db
+    }
+
+    // Get taxicab distance
+    pub fn get_distance(s: &(i32, i32), e: &(i32, i32)) -> i32 {
+        (s.0 - e.0).abs() + (s.1 - e.1).abs()
+    }
+}
+
+// Parse a line into sensor point start + end + taxi cab distance
+pub fn parse_line(s: &str) -> SensorPoint {
+    let re = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();    
+    let points = re.captures(s).unwrap().iter().skip(1)
+     .map(|x| x.unwrap().as_str().parse::<i32>().unwrap())
+     .collect::<Vec<_>>();    
+    SensorPoint::from_vec(&points).unwrap()
+}
+
+// Sensors from the puzzle's sample. Public so the binary's tests can share it
+pub fn sample_pairs() -> Vec<SensorPoint> {
+    std::fs::read_to_string("sample.txt").unwrap()
+     .lines()
+     .map(parse_line)
+     .collect()
+}
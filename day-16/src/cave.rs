@@ -0,0 +1,193 @@
+use std::hash::{Hash, Hasher};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone)]
+pub struct Valve {
+    pub name: String,
+    pub flowrate: i64,
+    pub paths: Vec<String>
+}
+
+// Custom hash only on name
+impl Hash for Valve {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl Valve {
+    // Parses a valve object from a string
+    #[allow(clippy::should_implement_trait, clippy::iter_skip_next, clippy::redundant_field_names)]
+    pub fn from_str(s: &str) -> Self {
+        let chunks = s.split(" ").collect::<Vec<&str>>();
+        let flow: i64 = chunks[4].split("=").skip(1).next().unwrap().strip_suffix(";").unwrap().parse::<i64>().unwrap();
+        let mut paths = Vec::new();
+        for path in chunks.iter().rev() {
+            match *path {
+                "valves" => break,
+                "valve"  => break,
+                _        => paths.push(path.strip_suffix(",").unwrap_or(path).to_string()),
+            }
+        }
+        Self {
+            name: chunks[1].to_string(),
+            flowrate: flow,
+            paths: paths
+        }
+    }
+}
+
+// Shortest distances between the valves worth opening, over compact ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    // Valve names in id order
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    // distances[from][to], UNREACHABLE when there is no path
+    distances: Vec<Vec<u32>>,
+}
+
+impl DistanceMatrix {
+    pub const UNREACHABLE: u32 = u32::MAX;
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> Option<&str> {
+        self.names.get(id).map(|x| x.as_str())
+    }
+
+    // Distance between two valves by id, None if either id is out of range
+    pub fn get(&self, from: usize, to: usize) -> Option<u32> {
+        self.distances.get(from)?.get(to).copied()
+    }
+}
+
+// Cave structure is a map of valve name and all valve objects
+// plus the distance between the valves worth opening
+#[derive(Debug)]
+pub struct Cave {
+    pub map: HashMap<String, Valve>,
+    pub distances: DistanceMatrix,
+    // Every valve by index, with tunnels as lists of indices
+    index: HashMap<String, usize>,
+    tunnels: Vec<Vec<usize>>,
+}
+
+impl Cave {
+    // Builds a cave out of map of valves
+    pub fn new(map: HashMap<String, Valve>) -> Self {
+        let mut all: Vec<&String> = map.keys().collect();
+        all.sort();
+        let index: HashMap<String, usize> = all.iter().enumerate().map(|(i, &x)| (x.clone(), i)).collect();
+
+        // Tunnels can be walked both ways. Tunnels to valves missing from the
+        // map lead nowhere and are skipped
+        let mut tunnels = vec![Vec::new(); all.len()];
+        for v in map.values() {
+            for b in v.paths.iter().filter_map(|x| index.get(x).copied()) {
+                let a = index[&v.name];
+                tunnels[a].push(b);
+                tunnels[b].push(a);
+            }
+        }
+
+        let empty = DistanceMatrix { names: Vec::new(), ids: HashMap::new(), distances: Vec::new() };
+        let mut cave = Self { map, distances: empty, index, tunnels };
+        cave.distances = cave.build_distances();
+        cave
+    }
+
+    // Distance from a valve to every valve, by index
+    fn bfs(&self, from: usize) -> Vec<u32> {
+        let mut distances = vec![DistanceMatrix::UNREACHABLE; self.tunnels.len()];
+        distances[from] = 0;
+        let mut queue = VecDeque::from([from]);
+        while let Some(v) = queue.pop_front() {
+            for &next in &self.tunnels[v] {
+                if distances[next] == DistanceMatrix::UNREACHABLE {
+                    distances[next] = distances[v] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    // Search from each valve with a non-zero flow rate to find the distances
+    // between all of them
+    fn build_distances(&self) -> DistanceMatrix {
+        let mut names: Vec<String> = self.map.values()
+         .filter(|v| v.flowrate > 0)
+         .map(|v| v.name.clone())
+         .collect();
+        names.sort();
+        let distances = names.iter()
+         .map(|x| {
+            let all = self.bfs(self.index[x]);
+            names.iter().map(|y| all[self.index[y]]).collect()
+         })
+         .collect();
+        let ids = names.iter().enumerate().map(|(i, x)| (x.clone(), i)).collect();
+        DistanceMatrix { names, ids, distances }
+    }
+
+    // Distance from any valve to each valve in the distance matrix, by id
+    pub fn distances_from(&self, name: &str) -> Option<Vec<u32>> {
+        let all = self.bfs(*self.index.get(name)?);
+        Some(self.distances.names.iter().map(|y| all[self.index[y]]).collect())
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn sample_cave() -> Cave {
+    let valves = std::fs::read_to_string("sample.txt").unwrap()
+     .lines()
+     .map(|line| { let v = Valve::from_str(line); (v.name.clone(), v) })
+     .collect();
+    Cave::new(valves)
+}
+
+#[test]
+fn test_distance_matrix() {
+    let cave = sample_cave();
+    let matrix = &cave.distances;
+    assert_eq!(matrix.names(), &["BB", "CC", "DD", "EE", "HH", "JJ"]);
+    let (bb, hh, jj) = (matrix.id("BB").unwrap(), matrix.id("HH").unwrap(), matrix.id("JJ").unwrap());
+    assert_eq!(matrix.name(hh), Some("HH"));
+    assert_eq!(matrix.name(matrix.len()), None);
+    assert_eq!(matrix.get(bb, jj), Some(3));
+    assert_eq!(matrix.get(hh, jj), Some(7));
+    assert_eq!(matrix.get(bb, matrix.len()), None);
+    for a in 0..matrix.len() {
+        assert_eq!(matrix.get(a, a), Some(0));
+        for b in 0..matrix.len() {
+            assert_eq!(matrix.get(a, b), matrix.get(b, a));
+        }
+    }
+    assert_eq!(cave.distances_from("AA").unwrap(), vec![1, 2, 1, 2, 5, 2]);
+    assert_eq!(cave.distances_from("ZZ"), None);
+}
+
+#[test]
+fn test_unknown_tunnels_are_skipped() {
+    let valves = ["Valve AA has flow rate=0; tunnels lead to valves BB, XX", "Valve BB has flow rate=5; tunnel leads to valve AA"]
+     .into_iter()
+     .map(|line| { let v = Valve::from_str(line); (v.name.clone(), v) })
+     .collect();
+    let cave = Cave::new(valves);
+    assert_eq!(cave.distances_from("AA"), Some(vec![1]));
+}
@@ -0,0 +1,142 @@
+use crate::cave::Cave;
+use crate::search::{routes_per_mask, Network, SearchError};
+
+// Settings for planning which valves to open
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchConfig {
+    // Valve every agent starts at
+    pub start: String,
+    // Minutes available to each agent
+    pub time: i64,
+    // Number of agents working in parallel
+    pub agents: usize,
+}
+
+impl Default for SearchConfig {
+    // Settings for part one: a single agent at AA with 30 minutes
+    fn default() -> Self {
+        Self { start: "AA".to_string(), time: 30, agents: 1 }
+    }
+}
+
+// Valves one agent opens, in order, with the minute each is opened
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub steps: Vec<(String, i64)>,
+    pub pressure: i64,
+}
+
+// Best combination of routes found for all agents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub pressure: i64,
+    pub routes: Vec<Route>,
+}
+
+// Build a route from valve ids, timing each step from the start
+fn to_route(network: &Network, valves: &[usize], pressure: i64) -> Route {
+    let mut minute = 0;
+    let mut position = network.len();
+    let steps = valves.iter()
+     .map(|&v| {
+        minute += network.distance(position, v).expect("routes only visit reachable valves") as i64 + 1;
+        position = v;
+        (network.names()[v].clone(), minute)
+     })
+     .collect();
+    Route { steps, pressure }
+}
+
+// Find the routes that release the most pressure with several agents opening
+// valves at the same time. Agents never open the same valve, so the best plan
+// splits the valves into one set per agent. Starting from the best route for
+// each exact set, the best total for every set of valves with k agents is the
+// best over ways to give one subset to the k-th agent and the rest to the others
+pub fn plan(cave: &Cave, config: &SearchConfig) -> Result<Plan, SearchError> {
+    let network = Network::new(cave, &config.start)?;
+    let table = routes_per_mask(&network, config.time);
+    let full = (1 << network.len()) - 1;
+
+    // best[mask] is the most pressure the agents so far release using only the
+    // valves in mask, and choices[k][mask] the set the k-th agent opens
+    let mut best = vec![0; full + 1];
+    let mut choices: Vec<Vec<usize>> = Vec::with_capacity(config.agents);
+    for _ in 0..config.agents {
+        let mut next = vec![0; full + 1];
+        let mut choice = vec![0; full + 1];
+        for mask in 0..=full {
+            // Every subset of mask, including mask itself and the empty set
+            let mut subset = mask;
+            loop {
+                let pressure = table[subset].0 + best[mask ^ subset];
+                if pressure > next[mask] {
+                    next[mask] = pressure;
+                    choice[mask] = subset;
+                }
+                if subset == 0 {
+                    break;
+                }
+                subset = (subset - 1) & mask;
+            }
+        }
+        best = next;
+        choices.push(choice);
+    }
+
+    // Walk back through the choices to recover each agent's route
+    let mut mask = full;
+    let mut routes = Vec::with_capacity(config.agents);
+    for choice in choices.iter().rev() {
+        let subset = choice[mask];
+        routes.push(to_route(&network, &table[subset].1, table[subset].0));
+        mask ^= subset;
+    }
+    routes.reverse();
+    Ok(Plan { pressure: best[full], routes })
+}
+
+#[cfg(test)]
+use crate::cave::sample_cave;
+
+#[test]
+fn test_single_agent() {
+    let plan = plan(&sample_cave(), &SearchConfig::default()).unwrap();
+    assert_eq!(plan.pressure, 1651);
+    // Route from the puzzle's worked example
+    let steps: Vec<(&str, i64)> = plan.routes[0].steps.iter().map(|(v, t)| (v.as_str(), *t)).collect();
+    assert_eq!(steps, vec![("DD", 2), ("BB", 5), ("JJ", 9), ("HH", 17), ("EE", 21), ("CC", 24)]);
+}
+
+#[test]
+fn test_two_agents() {
+    let config = SearchConfig { time: 26, agents: 2, ..SearchConfig::default() };
+    let plan = plan(&sample_cave(), &config).unwrap();
+    assert_eq!(plan.pressure, 1707);
+    assert_eq!(plan.routes.iter().map(|r| r.pressure).sum::<i64>(), 1707);
+    let mut opened: Vec<&str> = plan.routes.iter().flat_map(|r| r.steps.iter().map(|(v, _)| v.as_str())).collect();
+    opened.sort();
+    assert_eq!(opened, vec!["BB", "CC", "DD", "EE", "HH", "JJ"]);
+}
+
+#[test]
+fn test_more_agents_and_other_starts() {
+    let cave = sample_cave();
+    // Extra agents never hurt, and with enough of them every valve opens
+    let mut previous = 0;
+    for agents in 0..=4 {
+        let plan = plan(&cave, &SearchConfig { time: 26, agents, ..SearchConfig::default() }).unwrap();
+        assert_eq!(plan.routes.len(), agents);
+        assert!(plan.pressure >= previous);
+        previous = plan.pressure;
+    }
+    // Starting at JJ, opening it takes one minute and releases 21 for each of the other 4
+    let plan = plan(&cave, &SearchConfig { start: "JJ".to_string(), time: 5, agents: 1 }).unwrap();
+    assert_eq!(plan.routes[0].steps[0], ("JJ".to_string(), 1));
+    assert_eq!(plan.pressure, 21 * 4);
+}
+
+#[test]
+fn test_unknown_start() {
+    let config = SearchConfig { start: "ZZ".to_string(), ..SearchConfig::default() };
+    assert_eq!(plan(&sample_cave(), &config), Err(SearchError::UnknownValve("ZZ".to_string())));
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::cave::{Cave, DistanceMatrix};
+
+// Most valves worth opening that can be searched. Routes are recorded for every
+// set of opened valves in tables of 2^n entries, so the search slows down too
+// much to be useful beyond this
+pub const MAX_VALVES: usize = 16;
+
+// Reasons a cave cannot be searched
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchError {
+    // Starting valve that is not in the cave
+    UnknownValve(String),
+    // More valves worth opening than MAX_VALVES
+    TooManyValves(usize),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::UnknownValve(name) => write!(f, "no valve named '{}'", name),
+            SearchError::TooManyValves(n)   => write!(f, "{} valves worth opening, at most {} can be searched", n, MAX_VALVES),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+// Valves worth opening, numbered by their id in the cave's distance matrix so
+// that a set of them fits in a bitmask
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network<'a> {
+    pub matrix: &'a DistanceMatrix,
+    pub flow: Vec<i64>,
+    // Distance from the starting valve to each valve
+    pub start: Vec<u32>,
+}
+
+impl<'a> Network<'a> {
+    // Take the valves worth opening from the cave's distance matrix, with
+    // distances measured from `start`. Fails if `start` is not a valve or there
+    // are too many to search
+    pub fn new(cave: &'a Cave, start: &str) -> Result<Self, SearchError> {
+        let matrix = &cave.distances;
+        if matrix.len() > MAX_VALVES {
+            return Err(SearchError::TooManyValves(matrix.len()));
+        }
+        let start = cave.distances_from(start).ok_or_else(|| SearchError::UnknownValve(start.to_string()))?;
+        Ok(Self {
+            matrix,
+            flow: matrix.names().iter().map(|x| cave.map[x].flowrate).collect(),
+            start,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.matrix.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matrix.is_empty()
+    }
+
+    pub fn names(&self) -> &'a [String] {
+        self.matrix.names()
+    }
+
+    // Distance between two valves, where position `len()` is the starting valve.
+    // None if there is no path between them
+    pub(crate) fn distance(&self, from: usize, to: usize) -> Option<u32> {
+        let d = if from == self.len() { self.start.get(to).copied() } else { self.matrix.get(from, to) }?;
+        (d != DistanceMatrix::UNREACHABLE).then_some(d)
+    }
+
+    // Valves that can still be reached and opened with some time to spare,
+    // along with the time left once they are open
+    fn moves(&self, position: usize, time: i64, opened: u64) -> impl Iterator<Item = (usize, i64)> + '_ {
+        (0..self.len())
+         .filter(move |v| opened & (1 << v) == 0)
+         .filter_map(move |v| Some((v, time - self.distance(position, v)? as i64 - 1)))
+         .filter(|&(_, left)| left > 0)
+    }
+}
+
+// Most pressure that can be released in `time` minutes starting from `position`
+// with the `opened` valves already open. Results are memoised on the full state
+fn best_from(network: &Network, memo: &mut HashMap<(usize, i64, u64), i64>, position: usize, time: i64, opened: u64) -> i64 {
+    if let Some(&best) = memo.get(&(position, time, opened)) {
+        return best;
+    }
+    let moves: Vec<(usize, i64)> = network.moves(position, time, opened).collect();
+    let best = moves.into_iter()
+     .map(|(v, left)| network.flow[v] * left + best_from(network, memo, v, left, opened | 1 << v))
+     .max()
+     .unwrap_or(0);
+    memo.insert((position, time, opened), best);
+    best
+}
+
+// Most pressure a single agent can release in `time` minutes
+pub fn best_pressure(network: &Network, time: i64) -> i64 {
+    best_from(network, &mut HashMap::new(), network.len(), time, 0)
+}
+
+// Visit every route, recording the most pressure released by each set of
+// opened valves and the order they were opened in
+fn visit(network: &Network, best: &mut [(i64, Vec<usize>)], route: &mut Vec<usize>, time: i64, opened: u64, pressure: i64) {
+    let entry = &mut best[opened as usize];
+    if pressure > entry.0 {
+        *entry = (pressure, route.clone());
+    }
+    let position = route.last().copied().unwrap_or(network.len());
+    for (v, left) in network.moves(position, time, opened) {
+        route.push(v);
+        visit(network, best, route, left, opened | 1 << v, pressure + network.flow[v] * left);
+        route.pop();
+    }
+}
+
+// Most pressure released in `time` minutes for each set of opened valves along
+// with the route that releases it, indexed by bitmask. Sets that no route
+// opens exactly hold zero and an empty route
+pub fn routes_per_mask(network: &Network, time: i64) -> Vec<(i64, Vec<usize>)> {
+    let mut best = vec![(0, Vec::new()); 1 << network.len()];
+    visit(network, &mut best, &mut Vec::new(), time, 0, 0);
+    best
+}
+
+// Most pressure released in `time` minutes for each set of opened valves,
+// indexed by bitmask
+pub fn best_per_mask(network: &Network, time: i64) -> Vec<i64> {
+    routes_per_mask(network, time).into_iter().map(|x| x.0).collect()
+}
+
+#[test]
+fn test_best_pressure() {
+    let cave = crate::cave::sample_cave();
+    let network = Network::new(&cave, "AA").unwrap();
+    assert_eq!(network.names(), &["BB", "CC", "DD", "EE", "HH", "JJ"]);
+    assert_eq!(best_pressure(&network, 30), 1651);
+    assert_eq!(best_per_mask(&network, 30).into_iter().max(), Some(1651));
+}
+
+#[test]
+fn test_network_errors() {
+    // A corridor of valves that are all worth opening
+    let valves = (0..=MAX_VALVES)
+     .map(|i| {
+        let paths = if i > 0 { vec![format!("V{}", i - 1)] } else { Vec::new() };
+        (format!("V{}", i), crate::cave::Valve { name: format!("V{}", i), flowrate: 1, paths })
+     })
+     .collect();
+    let cave = Cave::new(valves);
+    assert_eq!(Network::new(&cave, "V0"), Err(SearchError::TooManyValves(MAX_VALVES + 1)));
+    assert_eq!(Network::new(&crate::cave::sample_cave(), "ZZ"), Err(SearchError::UnknownValve("ZZ".to_string())));
+}
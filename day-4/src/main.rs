@@ -1,13 +1,16 @@
 extern crate utilities;
-use itertools::Itertools;
 pub mod range;
-use crate::range::Range;
+use crate::range::{ParseRangeError, Range};
 
+#[allow(clippy::needless_borrow)]
 fn main() {    
     let input: Vec<(Range, Range)> = utilities::lines_from_file("input.txt")
      .unwrap()
      .into_iter()
-     .map(|x| parse_line(&x))
+     .enumerate()
+     .filter_map(|(i, x)| parse_line(&x)
+        .map_err(|e| eprintln!("Skipping line {}: {}", i + 1, e))
+        .ok())
      .collect();
     
     let p1 = input.iter()
@@ -26,15 +29,16 @@ fn main() {
 }
 
 // Convert a line to a tuple of ranges
-fn parse_line(s: &str) -> (Range, Range) {
-    s.split(",")
-     .map(|x| Range::from_str(x).unwrap())
-     .next_tuple()
-     .unwrap()
+fn parse_line(s: &str) -> Result<(Range, Range), ParseRangeError> {
+    let (a, b) = s.split_once(',')
+     .ok_or_else(|| ParseRangeError::MissingSeparator(s.to_string()))?;
+    Ok((a.parse()?, b.parse()?))
 }
 
 #[test]
 fn test_parse_line() {
-    assert_eq!(parse_line("1-2,3-4"), (Range::new(1,2), Range::new(3,4)));
+    assert_eq!(parse_line("1-2,3-4"), Ok((Range::new(1,2), Range::new(3,4))));
+    assert_eq!(parse_line("1-2"), Err(ParseRangeError::MissingSeparator("1-2".to_string())));
+    assert_eq!(parse_line("1-2,4-3"), Err(ParseRangeError::Reversed("4-3".to_string())));
 }
 
@@ -1,63 +1,247 @@
-use itertools::Itertools;
+use std::fmt;
+use std::str::FromStr;
+use num::PrimInt;
+use utilities::IntervalSet;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct Range {
-    lower: i32,
-    upper: i32
+// Inclusive range of values. A range with lower > upper is empty
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Range<T = i32> {
+    lower: T,
+    upper: T
 }
 
 // Implement from trait to convert raw tuple to Range
-impl From<(i32, i32)> for Range {
-    fn from((a, b): (i32, i32)) -> Self {
+impl<T> From<(T, T)> for Range<T> {
+    fn from((a, b): (T, T)) -> Self {
         Self{ lower: a, upper: b }
     }
 }
 
-impl Range {
+// Errors produced while parsing a range such as `2-4`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseRangeError {
+    // No `-` between the bounds
+    MissingSeparator(String),
+    // A bound that could not be parsed
+    InvalidBound(String),
+    // The lower bound is greater than the upper bound
+    Reversed(String),
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRangeError::MissingSeparator(s) => write!(f, "expected a range like '2-4', found '{}'", s),
+            ParseRangeError::InvalidBound(s)     => write!(f, "invalid range bound '{}'", s),
+            ParseRangeError::Reversed(s)         => write!(f, "range '{}' ends before it starts", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+impl<T: FromStr + Ord> FromStr for Range<T> {
+    type Err = ParseRangeError;
+
+    // Parse `lower-upper`. A leading `-` is taken as the sign of the lower bound
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.char_indices().skip(1).find(|&(_, c)| c == '-').map(|(i, _)| i)
+         .ok_or_else(|| ParseRangeError::MissingSeparator(s.to_string()))?;
+        let bound = |x: &str| x.parse::<T>().map_err(|_| ParseRangeError::InvalidBound(x.to_string()));
+        let range = Range::new(bound(&s[..split])?, bound(&s[split + 1..])?);
+        if range.is_empty() {
+            return Err(ParseRangeError::Reversed(s.to_string()));
+        }
+        Ok(range)
+    }
+}
+
+impl<T: Ord> Range<T> {
     // Create a new range
-    pub fn new(l: i32, u: i32) -> Range {
+    pub fn new(l: T, u: T) -> Range<T> {
         Self{ lower: l, upper: u }
-    }    
+    }
+
+    pub fn lower(&self) -> &T {
+        &self.lower
+    }
+
+    pub fn upper(&self) -> &T {
+        &self.upper
+    }
 
-    // Create from string
-    pub fn from_str(s: &str) -> Option<Self> {
-        let t: (i32, i32) = s.split("-")
-         .map(|x| x.parse::<i32>().unwrap())
-         .next_tuple()
-         .unwrap();
-        Some(Range::from(t))
+    pub fn is_empty(&self) -> bool {
+        self.lower > self.upper
     }
 
-    // Check if one range contains another
-    pub fn contains(&self, other: &Range) -> bool {
-        other.lower >= self.lower && other.upper <= self.upper
+    // Check if a value is in the range
+    pub fn contains_value(&self, value: &T) -> bool {
+        *value >= self.lower && *value <= self.upper
+    }
+
+    // Check if one range contains another. Every range contains an empty range
+    pub fn contains(&self, other: &Range<T>) -> bool {
+        other.is_empty() || (other.lower >= self.lower && other.upper <= self.upper)
     }
 
     // Check if two ranges overlap at all
-    pub fn overlaps(&self, other: &Range) -> bool {
-        (self.lower >= other.lower && self.lower <= other.upper) ||
-        (self.upper >= other.lower && self.lower <= other.lower)
+    pub fn overlaps(&self, other: &Range<T>) -> bool {
+        !self.is_empty() && !other.is_empty() && self.lower <= other.upper && other.lower <= self.upper
+    }
+}
 
+impl<T: Ord + Copy> Range<T> {
+    // Values in both ranges
+    pub fn intersection(&self, other: &Range<T>) -> Option<Range<T>> {
+        let range = Range::new(self.lower.max(other.lower), self.upper.min(other.upper));
+        (!range.is_empty()).then_some(range)
+    }
+}
+
+impl<T: PrimInt> Range<T> {
+    // Values in either range, if they overlap or touch so that the result is
+    // a single range
+    pub fn union(&self, other: &Range<T>) -> Option<Range<T>> {
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if other.is_empty() {
+            return Some(*self);
+        }
+        let (first, second) = if self.lower <= other.lower { (self, other) } else { (other, self) };
+        if first.upper < T::max_value() && first.upper + T::one() < second.lower {
+            return None;
+        }
+        Some(Range::new(first.lower, first.upper.max(second.upper)))
+    }
+
+    // Values in this range but not in the other, as up to two ranges
+    pub fn difference(&self, other: &Range<T>) -> Vec<Range<T>> {
+        if !self.overlaps(other) {
+            return if self.is_empty() { Vec::new() } else { vec![*self] };
+        }
+        let mut parts = Vec::with_capacity(2);
+        if self.lower < other.lower {
+            parts.push(Range::new(self.lower, other.lower - T::one()));
+        }
+        if self.upper > other.upper {
+            parts.push(Range::new(other.upper + T::one(), self.upper));
+        }
+        parts
+    }
+
+    // Number of values in the range. This can be larger than T can hold, so
+    // it is counted in u128, saturating for a range of every 128 bit value
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        let span = match (self.lower.to_i128(), self.upper.to_i128()) {
+            (Some(l), Some(u)) => u.abs_diff(l),
+            // Only u128 bounds can fall outside i128
+            _ => self.upper.to_u128().unwrap() - self.lower.to_u128().unwrap(),
+        };
+        span.saturating_add(1)
+    }
+
+    // Iterate over the values in the range in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        let upper = self.upper;
+        let first = (!self.is_empty()).then_some(self.lower);
+        std::iter::successors(first, move |&x| (x < upper).then(|| x + T::one()))
+    }
+}
+
+impl<T: Copy + Into<i64>> Range<T> {
+    // Values covered by the range
+    pub fn to_set(&self) -> IntervalSet {
+        IntervalSet::from_interval(self.lower.into(), self.upper.into())
     }
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_range_contains() {
     let f = Range::new(1, 10);
     let s = Range::new(2, 9);
     assert_eq!(f.contains(&s), true);
+    assert!(!s.contains(&f));
+    assert!(s.contains(&Range::new(5, 4)));
 }
 
 #[test]
 fn test_range_from_string() {
-    assert_eq!(Range::from_str("7-8").unwrap(), Range::new(7, 8));
-    assert_eq!(Range::from_str("1-5").unwrap(), Range::new(1, 5));
-    assert_eq!(Range::from_str("14-33").unwrap(), Range::new(14, 33));
+    assert_eq!("7-8".parse(), Ok(Range::new(7, 8)));
+    assert_eq!("1-5".parse(), Ok(Range::new(1, 5)));
+    assert_eq!("14-33".parse(), Ok(Range::new(14, 33)));
+    assert_eq!("-3--1".parse(), Ok(Range::new(-3, -1)));
+    assert_eq!("100-200".parse(), Ok(Range::<u64>::new(100, 200)));
+}
+
+#[test]
+fn test_range_parse_errors() {
+    assert_eq!("7".parse::<Range>(), Err(ParseRangeError::MissingSeparator("7".to_string())));
+    assert_eq!("7-x".parse::<Range>(), Err(ParseRangeError::InvalidBound("x".to_string())));
+    assert_eq!("-1-3".parse::<Range<u8>>(), Err(ParseRangeError::InvalidBound("-1".to_string())));
+    assert_eq!("9-8".parse::<Range>(), Err(ParseRangeError::Reversed("9-8".to_string())));
+    assert_eq!(ParseRangeError::Reversed("9-8".to_string()).to_string(), "range '9-8' ends before it starts");
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_ranges_overlap() {    
     assert_eq!(Range::new(1, 10).overlaps(&Range::new(2, 9)), true);
     assert_eq!(Range::new(7, 9).overlaps(&Range::new(8, 10)), true);
     assert_eq!(Range::new(1, 5).overlaps(&Range::new(0, 2)), true);
+}
+
+#[test]
+fn test_wide_ranges() {
+    // Types that do not fit in an i64 compare on their endpoints
+    assert!(Range::new(0u64, u64::MAX).contains(&Range::new(u64::MAX - 1, u64::MAX)));
+    assert!(Range::new(0usize, 5).overlaps(&Range::new(5, usize::MAX)));
+    assert!(!Range::new(i128::MIN, -1).overlaps(&Range::new(0, i128::MAX)));
+    assert!(Range::new(1u128, u128::MAX).contains(&Range::new(u128::MAX, u128::MAX)));
+    assert!(!Range::new(1u128, 2).contains(&Range::new(0, 2)));
+}
+
+#[test]
+fn test_overlap_is_symmetric() {
+    let ranges = [Range::new(1, 10), Range::new(2, 9), Range::new(10, 12), Range::new(11, 11), Range::new(0, 0)];
+    for a in &ranges {
+        for b in &ranges {
+            assert_eq!(a.overlaps(b), b.overlaps(a), "{:?} {:?}", a, b);
+            assert_eq!(a.overlaps(b), !a.to_set().intersect(&b.to_set()).is_empty());
+        }
+    }
+    assert!(!Range::new(1, 10).overlaps(&Range::new(11, 11)));
+    assert!(Range::new(1, 10).overlaps(&Range::new(10, 12)));
+}
+
+#[test]
+fn test_range_algebra() {
+    let a = Range::new(1u32, 10);
+    assert_eq!(a.intersection(&Range::new(5, 20)), Some(Range::new(5, 10)));
+    assert_eq!(a.intersection(&Range::new(11, 20)), None);
+    assert_eq!(a.union(&Range::new(11, 20)), Some(Range::new(1, 20)));
+    assert_eq!(a.union(&Range::new(12, 20)), None);
+    assert_eq!(a.difference(&Range::new(4, 6)), vec![Range::new(1, 3), Range::new(7, 10)]);
+    assert_eq!(a.difference(&Range::new(0, 6)), vec![Range::new(7, 10)]);
+    assert_eq!(a.difference(&Range::new(0, 20)), vec![]);
+    assert_eq!(a.len(), 10);
+    assert_eq!(Range::new(3u8, 255).iter().count(), 253);
+    assert_eq!(Range::new(-1i64, 2).iter().collect::<Vec<_>>(), vec![-1, 0, 1, 2]);
+    assert_eq!(Range::new(2, 1).iter().count(), 0);
+}
+
+#[test]
+fn test_range_len_wider_than_type() {
+    assert_eq!(Range::new(0u8, 255).len(), 256);
+    assert_eq!(Range::new(i8::MIN, i8::MAX).len(), 256);
+    assert_eq!(Range::new(-100i8, 100).len(), 201);
+    assert_eq!(Range::new(0i32, i32::MAX).len(), 1 << 31);
+    assert_eq!(Range::new(i128::MIN, i128::MAX).len(), u128::MAX);
+    assert_eq!(Range::new(1u128, u128::MAX).len(), u128::MAX);
+    assert_eq!(Range::new(5u8, 4).len(), 0);
 }
\ No newline at end of file
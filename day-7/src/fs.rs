@@ -1,3 +1,4 @@
+use std::cell::OnceCell;
 use std::collections::HashSet;
 
 pub use self::file::File;
@@ -6,6 +7,7 @@ pub use self::directory_info::DirectoryInfo;
 mod file;
 mod directory;
 mod directory_info;
+mod persist;
 
 // Actions that can be taken in the simplified file system
 #[derive(Debug, Clone, PartialEq)]
@@ -17,22 +19,35 @@ pub enum Action {
 }
 
 // Represents a filesystem hierarchy
+#[derive(Debug, Clone)]
 pub struct FileSystem {
     directories: Vec<Directory>,
-    current: usize
+    current: usize,
+    sizes: OnceCell<Vec<usize>>
+}
+
+// Filesystems are equal when they hold the same directories and are in the
+// same current directory, whether or not their sizes have been cached
+impl PartialEq for FileSystem {
+    fn eq(&self, other: &Self) -> bool {
+        self.directories == other.directories && self.current == other.current
+    }
 }
 
 impl FileSystem {
     // Create a new filesystem
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         let root = Directory::new("/");
         Self {
             directories: vec![root],
-            current: 0
+            current: 0,
+            sizes: OnceCell::new()
         }
     }
 
     // Move up one directory from current
+    #[allow(clippy::unused_unit)]
     pub fn go_up_one(&mut self) -> () {
         match self.directories[self.current].parent {
             Some(i) => self.current = i,
@@ -41,11 +56,13 @@ impl FileSystem {
     }
 
     // Change to root directory
+    #[allow(clippy::unused_unit)]
     pub fn go_to_root(&mut self) -> () {
         self.current = 0
     }
 
     // Move down a directory - assumes directory actually exists**
+    #[allow(clippy::unused_unit)]
     pub fn go_down_to(&mut self, dir: &str) -> () {        
         for i in &self.directories[self.current].directories {
             if self.directories[*i].has_name(dir) {
@@ -55,40 +72,109 @@ impl FileSystem {
         }
     }
 
+    // Get the absolute path of the current directory
+    pub fn current_path(&self) -> &str {
+        &self.directories[self.current].path
+    }
+
+    // Check if the current directory contains a subdirectory with the given name
+    pub fn has_directory(&self, name: &str) -> bool {
+        self.directories[self.current].directories.iter()
+         .any(|&i| self.directories[i].has_name(name))
+    }
+
+    // Find a file by name in the current directory
+    pub fn get_file(&self, name: &str) -> Option<&File> {
+        self.directories[self.current].files.iter()
+         .find(|f| f.get_name() == name)
+    }
+
+    // Append a new directory below a parent, returning its index
+    fn push_directory(&mut self, parent: usize, name: &str) -> usize {
+        let path = self.directories[parent].child_path(name);
+        self.directories.push(Directory::with_parent(parent, name, &path));
+        let index = self.directories.len() - 1;
+        self.directories[parent].directories.push(index);
+        self.sizes.take();
+        index
+    }
+
     // Add a new directory to the current directory
+    #[allow(clippy::unused_unit)]
     pub fn add_directory(&mut self, name: &str) -> () {
-        self.directories.push(Directory::with_parent(self.current, name));
-        let newest_index = self.directories.len() - 1 as usize;
-        self.directories[self.current].directories.push(newest_index);
+        self.push_directory(self.current, name);
     }
 
     // Add a new file to the current directory
+    #[allow(clippy::unused_unit)]
     pub fn add_file(&mut self, file: File) -> () {
         self.directories[self.current].files.push(file);
+        self.sizes.take();
     }
 
-    // Get size of the current directory
-    fn get_directory_size(&self, index: usize) -> usize {
-        let mut size = self.directories[index].files.iter()
-         .map(|x| x.get_size())
-         .fold(0, |sum, x| sum + x);
-        
-        for child in &self.directories[index].directories {
-            size += self.get_directory_size(*child);
+    // Compute the total size of every directory in a single post-order pass
+    fn compute_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.directories.iter()
+         .map(|d| d.files.iter().map(|x| x.get_size()).sum())
+         .collect();
+
+        // Reversing a pre-order walk visits every child before its parent
+        let mut order = Vec::with_capacity(self.directories.len());
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            stack.extend(&self.directories[i].directories);
+        }
+        for &i in order.iter().rev() {
+            if let Some(parent) = self.directories[i].parent {
+                sizes[parent] += sizes[i];
+            }
         }
-        return size;
+        sizes
+    }
+
+    // Get size of a directory. Sizes are cached until the filesystem is modified
+    fn get_directory_size(&self, index: usize) -> usize {
+        self.sizes.get_or_init(|| self.compute_sizes())[index]
+    }
+
+    // Build the summary info for a directory
+    fn get_directory_info(&self, index: usize) -> DirectoryInfo {
+        let d = &self.directories[index];
+        DirectoryInfo{ name: d.name.clone(), path: d.path.clone(), size: self.get_directory_size(index) }
+    }
+
+    // Get every directory along with its total size
+    pub fn get_directories(&self) -> Vec<DirectoryInfo> {
+        (0..self.directories.len())
+         .map(|i| self.get_directory_info(i))
+         .collect()
+    }
+
+    // Get all directories whose total size matches a predicate
+    pub fn find_directories<P>(&self, predicate: P) -> Vec<DirectoryInfo>
+    where
+        P: Fn(usize) -> bool
+    {
+        (0..self.directories.len())
+         .filter(|&i| predicate(self.get_directory_size(i)))
+         .map(|i| self.get_directory_info(i))
+         .collect()
     }
 
     // Get all directories with size equal to or below the limit
     pub fn get_directories_with_size_below(&self, limit: usize) -> HashSet<DirectoryInfo> {
-        let mut set = HashSet::new();
-        for i in 0..self.directories.len() {
-            let d_size = self.get_directory_size(i);
-            if d_size <= limit {
-                set.insert(DirectoryInfo{ name: self.directories[i].name.clone(), size: d_size });
-            }
-        }
-        set
+        self.find_directories(|size| size <= limit)
+         .into_iter()
+         .collect()
+    }
+
+    // Get the n largest directories, biggest first
+    pub fn get_largest_directories(&self, n: usize) -> Vec<DirectoryInfo> {
+        let mut dirs = self.get_directories();
+        dirs.sort_by(|a, b| b.cmp(a));
+        dirs.truncate(n);
+        dirs
     }
 
     // Get total used size
@@ -96,16 +182,103 @@ impl FileSystem {
         self.get_directory_size(0)
     }
 
-    // Get first directory bigger than some value
-    pub fn get_first_directory_bigger_than(&self, limit: usize) -> DirectoryInfo {
-        let mut dirs = Vec::new();
-        for i in 0..self.directories.len() {
-            let d_size = self.get_directory_size(i);
-            if d_size >= limit {
-                dirs.push(DirectoryInfo{ name: self.directories[i].name.clone(), size: d_size });
+    // Get the smallest directory with a size equal to or above the limit, if any
+    pub fn get_first_directory_bigger_than(&self, limit: usize) -> Option<DirectoryInfo> {
+        self.find_directories(|size| size >= limit)
+         .into_iter()
+         .min()
+    }
+
+    // Render the hierarchy as an indented tree showing the size of every entry,
+    // with directories and files sorted by name
+    pub fn render_tree(&self) -> String {
+        let mut out = String::new();
+        self.render_directory(0, 0, &mut out);
+        out
+    }
+
+    // Render a directory and its contents at the given depth
+    fn render_directory(&self, index: usize, depth: usize, out: &mut String) {
+        let d = &self.directories[index];
+        out.push_str(&format!("{}- {} (dir, size={})\n", "  ".repeat(depth), d.name, self.get_directory_size(index)));
+
+        let mut children: Vec<(&str, Option<usize>, usize)> = d.directories.iter()
+         .map(|&i| (self.directories[i].name.as_str(), Some(i), 0))
+         .chain(d.files.iter().map(|f| (f.get_name(), None, f.get_size())))
+         .collect();
+        children.sort_by_key(|(name, _, _)| *name);
+
+        for (name, dir, size) in children {
+            match dir {
+                Some(i) => self.render_directory(i, depth + 1, out),
+                None    => out.push_str(&format!("{}- {} (file, size={})\n", "  ".repeat(depth + 1), name, size))
             }
         }
-        dirs.sort();
-        dirs[0].clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn build_nested() -> FileSystem {
+    // Two directories named `a` at different depths
+    let mut fs = FileSystem::new();
+    fs.add_directory("a");
+    fs.add_directory("b");
+    fs.add_file(File::new("x", 10));
+    fs.go_down_to("b");
+    fs.add_directory("a");
+    fs.add_file(File::new("y", 20));
+    fs.go_down_to("a");
+    fs.add_file(File::new("z", 30));
+    fs.go_to_root();
+    fs.go_down_to("a");
+    fs.add_file(File::new("w", 40));
+    fs
+}
+
+#[test]
+fn test_repeated_directory_names() {
+    let fs = build_nested();
+    let dirs = fs.get_directories_with_size_below(40);
+    let mut paths: Vec<(&str, usize)> = dirs.iter().map(|d| (d.path.as_str(), d.size)).collect();
+    paths.sort();
+    assert_eq!(paths, vec![("/a", 40), ("/b/a", 30)]);
+}
+
+#[test]
+fn test_size_queries() {
+    let mut fs = build_nested();
+    assert_eq!(fs.get_used_size(), 100);
+    let largest: Vec<String> = fs.get_largest_directories(2).into_iter().map(|d| d.path).collect();
+    assert_eq!(largest, vec!["/", "/b"]);
+    assert_eq!(fs.get_first_directory_bigger_than(45).map(|d| d.path), Some("/b".to_string()));
+    assert_eq!(fs.get_first_directory_bigger_than(101), None);
+    assert_eq!(fs.find_directories(|size| size % 20 == 0).len(), 2);
+
+    // Mutations invalidate the cached sizes (current directory is /a)
+    fs.add_file(File::new("v", 5));
+    assert_eq!(fs.get_used_size(), 105);
+    assert_eq!(fs.get_first_directory_bigger_than(45).map(|d| d.path), Some("/a".to_string()));
+}
+
+#[test]
+fn test_equality_ignores_cached_sizes() {
+    let fs = build_nested();
+    let queried = build_nested();
+    assert_eq!(queried.get_used_size(), 100);
+    assert_eq!(fs, queried);
+}
+
+#[test]
+fn test_render_tree() {
+    let fs = build_nested();
+    assert_eq!(fs.render_tree(), "\
+- / (dir, size=100)
+  - a (dir, size=40)
+    - w (file, size=40)
+  - b (dir, size=50)
+    - a (dir, size=30)
+      - z (file, size=30)
+    - y (file, size=20)
+  - x (file, size=10)
+");
+}
@@ -5,6 +5,7 @@ use crate::fs::file::File;
 pub struct Directory {
     pub parent: Option<usize>,
     pub name: String,
+    pub path: String,
     pub directories: Vec<usize>,
     pub files: Vec<File>
 }
@@ -15,16 +16,18 @@ impl Directory {
         Self {
             parent: None,
             name: name.to_string(),
+            path: name.to_string(),
             directories: Vec::new(),
             files: Vec::new()
         }
     }
 
-    // Create a directory with a parent node
-    pub fn with_parent(parent: usize, name: &str) -> Self {
+    // Create a directory with a parent node and absolute path
+    pub fn with_parent(parent: usize, name: &str, path: &str) -> Self {
         Self {
             parent: Some(parent),
             name: name.to_string(),
+            path: path.to_string(),
             directories: Vec::new(),
             files: Vec::new()
         }
@@ -34,4 +37,13 @@ impl Directory {
     pub fn has_name(&self, name: &str) -> bool {
         self.name.eq(name)
     }
-}
\ No newline at end of file
+
+    // Absolute path of a child entry of this directory
+    pub fn child_path(&self, name: &str) -> String {
+        if self.path.ends_with('/') {
+            format!("{}{}", self.path, name)
+        } else {
+            format!("{}/{}", self.path, name)
+        }
+    }
+}
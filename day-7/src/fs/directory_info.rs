@@ -4,13 +4,14 @@ use std::cmp::Ordering;
 #[derive(Debug, Clone, PartialEq)]
 pub struct DirectoryInfo {
     pub name: String,
+    pub path: String,
     pub size: usize
 }
 
-// Hash directory info only on name
+// Hash directory info only on absolute path, since names can repeat
 impl Hash for DirectoryInfo {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+        self.path.hash(state);
     }
 }
 
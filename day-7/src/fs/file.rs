@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct File {
     name: String,
     size: usize
@@ -6,6 +8,7 @@ pub struct File {
 
 impl File{
     // Create a new file object
+    #[allow(clippy::redundant_field_names)]
     pub fn new(name: &str, size: usize) -> Self {
         Self {
             name: name.to_string(),
@@ -13,6 +16,11 @@ impl File{
         }
     }
 
+    // Get the name of the current file
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
     // Get the size of the current file
     pub fn get_size(&self) -> usize {
         self.size
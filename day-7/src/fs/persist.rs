@@ -0,0 +1,137 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{de, Deserialize, Serialize};
+
+use super::{File, FileSystem};
+
+// Nested form of a directory used for serialization
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Node {
+    name: String,
+    #[serde(default)]
+    files: Vec<File>,
+    #[serde(default)]
+    directories: Vec<Node>
+}
+
+impl FileSystem {
+    // Serialize the hierarchy as a nested JSON document
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.to_node(0))
+    }
+
+    // Build a filesystem from a nested JSON document. The root node must be named "/"
+    pub fn from_json(s: &str) -> Result<FileSystem, serde_json::Error> {
+        let root: Node = serde_json::from_str(s)?;
+        if root.name != "/" {
+            return Err(de::Error::custom(format!("root directory must be named '/', found '{}'", root.name)));
+        }
+        let mut fs = FileSystem::new();
+        fs.add_node(0, &root);
+        Ok(fs)
+    }
+
+    // Materialise the hierarchy below an existing directory. Files are created
+    // sparse with their recorded length, so large trees take no real space
+    pub fn write_to_disk(&self, root: impl AsRef<Path>) -> io::Result<()> {
+        self.write_directory(0, root.as_ref())
+    }
+
+    // Build a filesystem by walking a real directory. Files contribute their
+    // apparent length and symbolic links are ignored
+    pub fn from_disk(root: impl AsRef<Path>) -> io::Result<FileSystem> {
+        let mut fs = FileSystem::new();
+        fs.read_directory(0, root.as_ref())?;
+        Ok(fs)
+    }
+
+    // Convert a directory and its descendants to the nested form
+    fn to_node(&self, index: usize) -> Node {
+        let d = &self.directories[index];
+        Node {
+            name: d.name.clone(),
+            files: d.files.clone(),
+            directories: d.directories.iter().map(|&i| self.to_node(i)).collect()
+        }
+    }
+
+    // Add the contents of a nested node below an existing directory
+    fn add_node(&mut self, index: usize, node: &Node) {
+        self.directories[index].files.extend(node.files.iter().cloned());
+        for child in &node.directories {
+            let child_index = self.push_directory(index, &child.name);
+            self.add_node(child_index, child);
+        }
+    }
+
+    // Write the contents of a directory into an existing path
+    fn write_directory(&self, index: usize, path: &Path) -> io::Result<()> {
+        let d = &self.directories[index];
+        for f in &d.files {
+            let file = fs::File::create(path.join(checked_name(f.get_name())?))?;
+            file.set_len(f.get_size() as u64)?;
+        }
+        for &i in &d.directories {
+            let child = path.join(checked_name(&self.directories[i].name)?);
+            fs::create_dir_all(&child)?;
+            self.write_directory(i, &child)?;
+        }
+        Ok(())
+    }
+
+    // Add the contents of a real directory below an existing directory
+    fn read_directory(&mut self, index: usize, path: &Path) -> io::Result<()> {
+        // Sort entries so the result does not depend on directory iteration order
+        let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                let child = self.push_directory(index, &name);
+                self.read_directory(child, &entry.path())?;
+            } else if file_type.is_file() {
+                let size = entry.metadata()?.len() as usize;
+                self.directories[index].files.push(File::new(&name, size));
+            }
+        }
+        self.sizes.take();
+        Ok(())
+    }
+}
+
+// Reject entry names that would escape the target directory
+fn checked_name(name: &str) -> io::Result<&str> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid entry name '{}'", name)));
+    }
+    Ok(name)
+}
+
+#[test]
+fn test_json_round_trip() {
+    let mut fs = FileSystem::new();
+    fs.add_directory("a");
+    fs.add_file(File::new("b.txt", 10));
+    fs.go_down_to("a");
+    fs.add_file(File::new("c", 5));
+
+    let json = fs.to_json().unwrap();
+    let parsed = FileSystem::from_json(&json).unwrap();
+    assert_eq!(parsed.render_tree(), fs.render_tree());
+    assert_eq!(parsed.get_used_size(), 15);
+
+    let minimal = FileSystem::from_json(r#"{"name": "/", "directories": [{"name": "x"}]}"#).unwrap();
+    assert_eq!(minimal.get_directories().len(), 2);
+    assert!(FileSystem::from_json("[]").is_err());
+    assert!(FileSystem::from_json(r#"{"name": "x"}"#).is_err());
+}
+
+#[test]
+fn test_checked_name() {
+    assert!(checked_name("a.txt").is_ok());
+    assert!(checked_name("..").is_err());
+    assert!(checked_name("a/b").is_err());
+}
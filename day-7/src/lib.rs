@@ -0,0 +1,3 @@
+extern crate utilities;
+pub mod fs;
+pub mod transcript;
@@ -0,0 +1,222 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::fs::{Action, File, FileSystem};
+
+// Parse result for an input line
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineResult {
+    Action(Action),
+    Directory(String),
+    File(File)
+}
+
+// How the replay engine reacts to problems in the transcript
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Mode {
+    // Record the problem, recover and keep going
+    Lenient,
+    // Stop at the first problem
+    Strict
+}
+
+// Problems that can be found while replaying a transcript. Line numbers start at 1
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    // Line could not be parsed as a command or listing entry
+    Malformed { line: usize, text: String },
+    // Listing output appeared without a preceding `ls`
+    UnexpectedOutput { line: usize },
+    // A directory was listed more than once
+    RepeatedListing { line: usize, path: String },
+    // `cd` into a directory that has not been listed
+    UnknownDirectory { line: usize, path: String, name: String },
+    // The same entry appeared more than once in a single listing
+    DuplicateEntry { line: usize, path: String, name: String },
+    // A file was listed again with a different size
+    InconsistentSize { line: usize, path: String, name: String, expected: usize, found: usize },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Malformed { line, text } =>
+                write!(f, "line {}: cannot parse '{}'", line, text),
+            Issue::UnexpectedOutput { line } =>
+                write!(f, "line {}: output without a preceding ls", line),
+            Issue::RepeatedListing { line, path } =>
+                write!(f, "line {}: {} has already been listed", line, path),
+            Issue::UnknownDirectory { line, path, name } =>
+                write!(f, "line {}: {} has no directory named {}", line, path, name),
+            Issue::DuplicateEntry { line, path, name } =>
+                write!(f, "line {}: {} lists {} more than once", line, path, name),
+            Issue::InconsistentSize { line, path, name, expected, found } =>
+                write!(f, "line {}: {} in {} has size {} but was previously {}", line, name, path, found, expected),
+        }
+    }
+}
+
+// A filesystem rebuilt from a transcript along with any problems found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub filesystem: FileSystem,
+    pub issues: Vec<Issue>
+}
+
+// Rebuild a filesystem from a shell transcript.
+// In lenient mode problems are recorded and recovered from: repeated entries are only
+// counted once, the first size seen for a file wins and `cd` into an unknown directory
+// creates it. In strict mode the first problem is returned as an error
+pub fn replay<S: AsRef<str>>(lines: &[S], mode: Mode) -> Result<Replay, Issue> {
+    let mut fs = FileSystem::new();
+    let mut issues = Vec::new();
+    let mut listed: HashSet<String> = HashSet::new();
+    let mut listing: Option<HashSet<String>> = None;
+
+    let mut report = |issue: Issue| -> Result<(), Issue> {
+        match mode {
+            Mode::Strict  => Err(issue),
+            Mode::Lenient => {
+                issues.push(issue);
+                Ok(())
+            }
+        }
+    };
+
+    for (i, text) in lines.iter().enumerate() {
+        let line = i + 1;
+        let parsed = match parse_line(text.as_ref()) {
+            Some(p) => p,
+            None    => {
+                report(Issue::Malformed { line, text: text.as_ref().to_string() })?;
+                continue;
+            }
+        };
+
+        // Name of the entry produced by a listing line
+        let name = match &parsed {
+            LineResult::Action(a) => {
+                listing = None;
+                match a {
+                    Action::ListAll  => {
+                        let path = fs.current_path().to_string();
+                        if !listed.insert(path.clone()) {
+                            report(Issue::RepeatedListing { line, path })?;
+                        }
+                        listing = Some(HashSet::new());
+                    },
+                    Action::GotoRoot => fs.go_to_root(),
+                    Action::GoUpOne  => fs.go_up_one(),
+                    Action::GoDownTo(dir) => {
+                        if !fs.has_directory(dir) {
+                            report(Issue::UnknownDirectory { line, path: fs.current_path().to_string(), name: dir.clone() })?;
+                            fs.add_directory(dir);
+                        }
+                        fs.go_down_to(dir);
+                    }
+                }
+                continue;
+            },
+            LineResult::Directory(d) => d.clone(),
+            LineResult::File(f)      => f.get_name().to_string()
+        };
+
+        let seen = match listing.as_mut() {
+            Some(seen) => seen,
+            None       => {
+                report(Issue::UnexpectedOutput { line })?;
+                continue;
+            }
+        };
+        if !seen.insert(name.clone()) {
+            report(Issue::DuplicateEntry { line, path: fs.current_path().to_string(), name })?;
+            continue;
+        }
+
+        match parsed {
+            LineResult::Directory(d) => {
+                if !fs.has_directory(&d) {
+                    fs.add_directory(&d);
+                }
+            },
+            LineResult::File(f) => match fs.get_file(f.get_name()).map(|x| x.get_size()) {
+                None => fs.add_file(f),
+                Some(expected) if expected != f.get_size() => {
+                    let path = fs.current_path().to_string();
+                    report(Issue::InconsistentSize { line, path, name, expected, found: f.get_size() })?;
+                },
+                Some(_) => ()
+            },
+            LineResult::Action(_) => unreachable!()
+        }
+    }
+    Ok(Replay { filesystem: fs, issues })
+}
+
+// Parse a line into either a console command, or a output result
+pub fn parse_line(s: &str) -> Option<LineResult> {
+    let items: Vec<&str> = s.split(' ').collect();
+    match items.as_slice() {
+        ["$", ..]       => parse_action(s).map(LineResult::Action),
+        ["dir", name]   => Some(LineResult::Directory(name.to_string())),
+        [size, name]    => size.parse::<usize>().ok().map(|x| LineResult::File(File::new(name, x))),
+        _               => None
+    }
+}
+
+// Parse a line into a filesystem action
+pub fn parse_action(s: &str) -> Option<Action> {
+    let items: Vec<&str> = s.split(' ').collect();
+    match items.as_slice() {
+        ["$", "ls"]       => Some(Action::ListAll),
+        ["$", "cd", "/"]  => Some(Action::GotoRoot),
+        ["$", "cd", ".."] => Some(Action::GoUpOne),
+        ["$", "cd", dir]  => Some(Action::GoDownTo(dir.to_string())),
+        _                 => None
+    }
+}
+
+#[test]
+fn test_parse_action() {
+    assert_eq!(parse_action("$ cd /"), Some(Action::GotoRoot));
+    assert_eq!(parse_action("$ ls"), Some(Action::ListAll));
+    assert_eq!(parse_action("$ cd .."), Some(Action::GoUpOne));
+    assert_eq!(parse_action("$ cd d"), Some(Action::GoDownTo("d".to_string())));
+    assert_eq!(parse_action("$ rm d"), None);
+}
+
+#[test]
+fn test_parse_line() {
+    assert_eq!(parse_line("dir a"), Some(LineResult::Directory("a".to_string())));
+    assert_eq!(parse_line("584 i"), Some(LineResult::File(File::new("i", 584))));
+    assert_eq!(parse_line("big i"), None);
+}
+
+#[test]
+fn test_replay_sample() {
+    let lines = utilities::lines_from_file("sample.txt").unwrap();
+    let result = replay(&lines, Mode::Strict).unwrap();
+    assert!(result.issues.is_empty());
+    assert_eq!(result.filesystem.get_used_size(), 48381165);
+}
+
+#[test]
+fn test_replay_issues() {
+    let lines = [
+        "$ cd /", "$ ls", "dir a", "10 x", "10 x",
+        "$ cd a", "$ ls", "5 y",
+        "$ cd ..", "$ ls", "dir a", "20 x",
+        "$ cd b", "7 z",
+    ];
+    let result = replay(&lines, Mode::Lenient).unwrap();
+    assert_eq!(result.issues, vec![
+        Issue::DuplicateEntry { line: 5, path: "/".to_string(), name: "x".to_string() },
+        Issue::RepeatedListing { line: 10, path: "/".to_string() },
+        Issue::InconsistentSize { line: 12, path: "/".to_string(), name: "x".to_string(), expected: 10, found: 20 },
+        Issue::UnknownDirectory { line: 13, path: "/".to_string(), name: "b".to_string() },
+        Issue::UnexpectedOutput { line: 14 },
+    ]);
+    assert_eq!(result.filesystem.get_used_size(), 15);
+
+    assert_eq!(replay(&lines, Mode::Strict), Err(Issue::DuplicateEntry { line: 5, path: "/".to_string(), name: "x".to_string() }));
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use day_7::fs::FileSystem;
+use day_7::transcript;
+
+// Independent du-style walk: total apparent size of every directory keyed by path
+fn disk_usage(root: &Path, path: &str, sizes: &mut HashMap<String, usize>) -> usize {
+    let mut total = 0;
+    for entry in std::fs::read_dir(root).unwrap() {
+        let entry = entry.unwrap();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type().unwrap().is_dir() {
+            let child = if path == "/" { format!("/{}", name) } else { format!("{}/{}", path, name) };
+            total += disk_usage(&entry.path(), &child, sizes);
+        } else {
+            total += entry.metadata().unwrap().len() as usize;
+        }
+    }
+    sizes.insert(path.to_string(), total);
+    total
+}
+
+fn sample_filesystem() -> FileSystem {
+    let lines = utilities::lines_from_file("sample.txt").unwrap();
+    transcript::replay(&lines, transcript::Mode::Strict).unwrap().filesystem
+}
+
+#[test]
+fn test_disk_round_trip_matches_du() -> std::io::Result<()> {
+    let fs = sample_filesystem();
+    let dir = tempfile::tempdir()?;
+    fs.write_to_disk(dir.path())?;
+
+    let mut du = HashMap::new();
+    disk_usage(dir.path(), "/", &mut du);
+    let expected: HashMap<String, usize> = fs.get_directories()
+     .into_iter()
+     .map(|d| (d.path, d.size))
+     .collect();
+    assert_eq!(du, expected);
+    assert_eq!(du["/"], 48381165);
+
+    let rebuilt = FileSystem::from_disk(dir.path())?;
+    assert_eq!(rebuilt.render_tree(), fs.render_tree());
+    Ok(())
+}
+
+#[test]
+fn test_json_round_trip() {
+    let fs = sample_filesystem();
+    let rebuilt = FileSystem::from_json(&fs.to_json().unwrap()).unwrap();
+    assert_eq!(rebuilt.get_directories_with_size_below(100000), fs.get_directories_with_size_below(100000));
+    assert_eq!(rebuilt.get_used_size(), 48381165);
+}
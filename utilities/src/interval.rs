@@ -0,0 +1,290 @@
+use std::fmt;
+
+/// A set of integers stored as sorted, disjoint inclusive intervals.
+/// Overlapping or touching intervals are merged on insert, so each
+/// interval is separated from the next by at least one missing value
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a set holding a single inclusive interval. The set is empty if
+    /// `lower > upper`
+    ///
+    /// # Examples
+    /// ```
+    /// let set = utilities::IntervalSet::from_interval(3, 7);
+    /// assert_eq!(set.len(), 5);
+    /// ```
+    pub fn from_interval(lower: i64, upper: i64) -> Self {
+        let mut set = Self::new();
+        set.insert(lower, upper);
+        set
+    }
+
+    /// Add the inclusive interval `lower..=upper`, merging it with any intervals
+    /// it overlaps or touches. Empty intervals are ignored
+    ///
+    /// # Arguments
+    /// * `lower` - First value of the interval
+    /// * `upper` - Last value of the interval
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = utilities::IntervalSet::new();
+    /// set.insert(1, 3);
+    /// set.insert(4, 6);
+    /// set.insert(10, 12);
+    /// assert_eq!(set.intervals(), &[(1, 6), (10, 12)]);
+    /// ```
+    pub fn insert(&mut self, lower: i64, upper: i64) {
+        if lower > upper {
+            return;
+        }
+        // Intervals entirely before or after the new one, allowing for touching ends
+        let start = self.intervals.partition_point(|&(_, u)| u < lower.saturating_sub(1));
+        let end = self.intervals.partition_point(|&(l, _)| l <= upper.saturating_add(1));
+        let merged = match &self.intervals[start..end] {
+            []   => (lower, upper),
+            near => (near[0].0.min(lower), near[near.len() - 1].1.max(upper)),
+        };
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    /// Remove every value in the inclusive interval `lower..=upper`
+    ///
+    /// # Examples
+    /// ```
+    /// let mut set = utilities::IntervalSet::from_interval(0, 10);
+    /// set.remove(3, 4);
+    /// assert_eq!(set.intervals(), &[(0, 2), (5, 10)]);
+    /// ```
+    pub fn remove(&mut self, lower: i64, upper: i64) {
+        if lower > upper {
+            return;
+        }
+        let start = self.intervals.partition_point(|&(_, u)| u < lower);
+        let end = self.intervals.partition_point(|&(l, _)| l <= upper);
+        let mut kept = Vec::with_capacity(2);
+        if let Some(&(l, _)) = self.intervals[start..end].first().filter(|(l, _)| *l < lower) {
+            kept.push((l, lower - 1));
+        }
+        if let Some(&(_, u)) = self.intervals[start..end].last().filter(|(_, u)| *u > upper) {
+            kept.push((upper + 1, u));
+        }
+        self.intervals.splice(start..end, kept);
+    }
+
+    /// Values in either set
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for &(l, u) in &other.intervals {
+            set.insert(l, u);
+        }
+        set
+    }
+
+    /// Values in this set but not in `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use utilities::IntervalSet;
+    /// let set = IntervalSet::from_interval(0, 9).subtract(&IntervalSet::from_interval(5, 20));
+    /// assert_eq!(set.intervals(), &[(0, 4)]);
+    /// ```
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for &(l, u) in &other.intervals {
+            set.remove(l, u);
+        }
+        set
+    }
+
+    /// Values in both sets
+    ///
+    /// # Examples
+    /// ```
+    /// use utilities::IntervalSet;
+    /// let a: IntervalSet = [(0, 5), (10, 15)].into_iter().collect();
+    /// let b = IntervalSet::from_interval(4, 11);
+    /// assert_eq!(a.intersect(&b).intervals(), &[(4, 5), (10, 11)]);
+    /// ```
+    pub fn intersect(&self, other: &IntervalSet) -> IntervalSet {
+        let (a, b) = (&self.intervals, &other.intervals);
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = Vec::new();
+        while i < a.len() && j < b.len() {
+            let (lower, upper) = (a[i].0.max(b[j].0), a[i].1.min(b[j].1));
+            if lower <= upper {
+                intervals.push((lower, upper));
+            }
+            if a[i].1 < b[j].1 { i += 1; } else { j += 1; }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// Check if a value is in the set
+    pub fn contains(&self, value: i64) -> bool {
+        let i = self.intervals.partition_point(|&(_, u)| u < value);
+        self.intervals.get(i).is_some_and(|&(l, _)| l <= value)
+    }
+
+    /// Number of values in the set. This is counted in u128, since a set of
+    /// every i64 holds 2^64 values
+    ///
+    /// # Examples
+    /// ```
+    /// let set = utilities::IntervalSet::from_interval(i64::MIN, i64::MAX);
+    /// assert_eq!(set.len(), 1 << 64);
+    /// ```
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(|&(l, u)| (u as i128 - l as i128 + 1) as u128).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Smallest and largest value in the set
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        Some((self.intervals.first()?.0, self.intervals.last()?.1))
+    }
+
+    /// The disjoint intervals making up the set, in ascending order
+    pub fn intervals(&self) -> &[(i64, i64)] {
+        &self.intervals
+    }
+
+    /// Iterate over the inclusive intervals of missing values between the
+    /// intervals of the set
+    ///
+    /// # Examples
+    /// ```
+    /// use utilities::IntervalSet;
+    /// let set: IntervalSet = [(0, 2), (5, 5), (8, 9)].into_iter().collect();
+    /// assert_eq!(set.gaps().collect::<Vec<_>>(), vec![(3, 4), (6, 7)]);
+    /// ```
+    pub fn gaps(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.intervals.windows(2).map(|w| (w[0].1 + 1, w[1].0 - 1))
+    }
+}
+
+impl FromIterator<(i64, i64)> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for (l, u) in iter {
+            set.insert(l, u);
+        }
+        set
+    }
+}
+
+impl fmt::Debug for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+         .entries(self.intervals.iter().map(|(l, u)| l..=u))
+         .finish()
+    }
+}
+
+#[cfg(test)]
+use std::collections::BTreeSet;
+
+// Small intervals over a narrow range so that overlaps are common
+#[cfg(test)]
+fn intervals() -> impl proptest::strategy::Strategy<Value = Vec<(i64, i64)>> {
+    use proptest::prelude::*;
+    proptest::collection::vec((-20i64..20, 0i64..8).prop_map(|(l, n)| (l, l + n)), 0..8)
+}
+
+// Plain set of values for comparison
+#[cfg(test)]
+fn model(intervals: &[(i64, i64)]) -> BTreeSet<i64> {
+    intervals.iter().flat_map(|&(l, u)| l..=u).collect()
+}
+
+#[cfg(test)]
+fn values(set: &IntervalSet) -> BTreeSet<i64> {
+    model(set.intervals())
+}
+
+// Intervals are sorted and separated by at least one missing value
+#[cfg(test)]
+fn is_normalised(set: &IntervalSet) -> bool {
+    set.intervals().iter().all(|(l, u)| l <= u) &&
+    set.intervals().windows(2).all(|w| w[0].1 + 1 < w[1].0)
+}
+
+#[test]
+fn test_touching_intervals_merge() {
+    let set: IntervalSet = [(5, 6), (1, 2), (3, 4)].into_iter().collect();
+    assert_eq!(set.intervals(), &[(1, 6)]);
+    assert_eq!(set.len(), 6);
+    assert_eq!(set.gaps().count(), 0);
+}
+
+#[test]
+fn test_full_range() {
+    let mut set = IntervalSet::from_interval(i64::MIN, i64::MAX);
+    assert_eq!(set.len(), 1 << 64);
+    set.remove(i64::MIN, i64::MIN);
+    set.remove(i64::MAX, i64::MAX);
+    assert_eq!(set.intervals(), &[(i64::MIN + 1, i64::MAX - 1)]);
+    assert_eq!(set.len(), (1 << 64) - 2);
+    set.insert(i64::MAX, i64::MAX);
+    assert_eq!(set.intervals(), &[(i64::MIN + 1, i64::MAX)]);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_insert(a in intervals()) {
+        let set: IntervalSet = a.iter().copied().collect();
+        proptest::prop_assert!(is_normalised(&set));
+        proptest::prop_assert_eq!(values(&set), model(&a));
+        proptest::prop_assert_eq!(set.len(), model(&a).len() as u128);
+    }
+
+    #[test]
+    fn test_set_operations(a in intervals(), b in intervals()) {
+        let (sa, sb): (IntervalSet, IntervalSet) = (a.iter().copied().collect(), b.iter().copied().collect());
+        let (ma, mb) = (model(&a), model(&b));
+        for (set, expected) in [
+            (sa.union(&sb), ma.union(&mb).copied().collect::<BTreeSet<_>>()),
+            (sa.subtract(&sb), ma.difference(&mb).copied().collect()),
+            (sa.intersect(&sb), ma.intersection(&mb).copied().collect()),
+        ] {
+            proptest::prop_assert!(is_normalised(&set));
+            proptest::prop_assert_eq!(values(&set), expected);
+        }
+    }
+
+    #[test]
+    fn test_len_at_extremes(l in proptest::num::i64::ANY, u in proptest::num::i64::ANY) {
+        let set = IntervalSet::from_interval(l, u);
+        let expected = if l > u { 0 } else { (u as i128 - l as i128 + 1) as u128 };
+        proptest::prop_assert_eq!(set.len(), expected);
+        let rest = IntervalSet::from_interval(i64::MIN, i64::MAX).subtract(&set);
+        proptest::prop_assert!(is_normalised(&rest));
+        proptest::prop_assert_eq!(rest.len() + set.len(), 1 << 64);
+    }
+
+    #[test]
+    fn test_gaps_and_contains(a in intervals(), x in -30i64..30) {
+        let set: IntervalSet = a.iter().copied().collect();
+        proptest::prop_assert_eq!(set.contains(x), model(&a).contains(&x));
+        let gaps: Vec<(i64, i64)> = set.gaps().collect();
+        if let Some((lower, upper)) = set.bounds() {
+            let missing: BTreeSet<i64> = (lower..=upper).filter(|x| !set.contains(*x)).collect();
+            proptest::prop_assert_eq!(model(&gaps), missing);
+        } else {
+            proptest::prop_assert!(gaps.is_empty());
+        }
+    }
+}
@@ -8,6 +8,9 @@ use std::{
 
 use itertools::Itertools;
 
+pub use self::interval::IntervalSet;
+mod interval;
+
 /// Read the lines from a file into a vector of strings
 /// 
 /// # Arguments